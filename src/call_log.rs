@@ -0,0 +1,114 @@
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rmcp::model::JsonObject;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Placeholder written in place of redacted argument values
+const REDACTED: &str = "[redacted]";
+
+/// Outcome of a recorded tool call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallStatus {
+    Ok,
+    Error,
+}
+
+/// One line of the call log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallRecord {
+    pub timestamp: DateTime<Utc>,
+    pub tool: String,
+    pub arguments: Option<JsonObject>,
+    /// Client that made the call, when it identified itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caller: Option<String>,
+    pub status: CallStatus,
+    /// ID of the item the call created, so a replay can keep it stable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_id: Option<String>,
+    /// Names of the arguments whose values were masked when the call was logged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redacted: Vec<String>,
+}
+
+impl CallRecord {
+    /// Whether any argument was masked when the call was logged
+    pub fn has_redacted_arguments(&self) -> bool {
+        !self.redacted.is_empty()
+    }
+}
+
+/// Append-only JSON Lines log of tool calls
+pub struct CallLog {
+    file: Mutex<File>,
+    redact: Vec<String>,
+}
+
+impl CallLog {
+    /// Open (or create) the log file for appending
+    ///
+    /// Values of any argument named in `redact` are replaced before writing.
+    pub fn open(path: &Path, redact: Vec<String>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(File::from_std(file)),
+            redact,
+        })
+    }
+
+    /// Append a record, logging rather than returning any write failure
+    pub async fn record(&self, mut record: CallRecord) {
+        if let Some(arguments) = record.arguments.as_mut() {
+            for key in &self.redact {
+                if let Some(value) = arguments.get_mut(key) {
+                    *value = Value::String(REDACTED.to_string());
+                    record.redacted.push(key.clone());
+                }
+            }
+        }
+
+        let mut line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("Failed to serialize call log record for {}: {}", record.tool, e);
+                return;
+            },
+        };
+        line.push('\n');
+
+        let mut file = self.file.lock().await;
+        if let Err(e) = file.write_all(line.as_bytes()).await {
+            tracing::error!("Failed to write call log: {}", e);
+            return;
+        }
+        if let Err(e) = file.flush().await {
+            tracing::error!("Failed to flush call log: {}", e);
+        }
+    }
+}
+
+/// Read every record from a call log, skipping blank lines
+pub fn read_records(path: &Path) -> io::Result<Vec<CallRecord>> {
+    let reader = BufReader::new(std::fs::File::open(path)?);
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line).map_err(|e| io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid call log record on line {}: {}", index + 1, e),
+        ))?;
+        records.push(record);
+    }
+    Ok(records)
+}
@@ -0,0 +1,137 @@
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Runtime configuration of the todo server
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Expose todo items as `todo://{id}` resources
+    pub resources: bool,
+    /// URL that receives a POST with the item JSON whenever a todo is completed
+    pub webhook_url: Option<String>,
+    /// How long after a completion the webhook waits, so it can be undone
+    pub completion_grace_period: Duration,
+    /// Simulate every tool call without applying any changes
+    pub dry_run: bool,
+    /// Maximum number of tool calls executed at once; excess calls wait
+    pub max_concurrent_calls: usize,
+    /// File to which every tool call is appended for later replay
+    pub call_log_path: Option<PathBuf>,
+    /// Argument names whose values are masked in the call log
+    pub call_log_redact: Vec<String>,
+    /// Longest title accepted for a todo item, in characters; `None` allows any length
+    pub max_title_chars: Option<usize>,
+    /// Return an existing unfinished item instead of creating a duplicate title
+    pub deduplicate_on_create: bool,
+    /// Items `list_todos` returns when no limit is given; `None` lists everything
+    pub list_default_limit: Option<usize>,
+    /// Directory `export_to_file` may write into; the tool is disabled when unset
+    pub export_dir: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            resources: true,
+            webhook_url: None,
+            completion_grace_period: Duration::from_secs(30),
+            dry_run: false,
+            max_concurrent_calls: 64,
+            call_log_path: None,
+            call_log_redact: Vec::new(),
+            max_title_chars: None,
+            deduplicate_on_create: false,
+            list_default_limit: None,
+            export_dir: None,
+        }
+    }
+}
+
+impl Config {
+    /// Build a configuration from `TODO_*` environment variables,
+    /// falling back to the defaults for anything unset
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            resources: env_flag("TODO_RESOURCES").unwrap_or(defaults.resources),
+            webhook_url: env::var("TODO_WEBHOOK_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .or(defaults.webhook_url),
+            completion_grace_period: env_value("TODO_COMPLETION_GRACE_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.completion_grace_period),
+            dry_run: env_flag("TODO_DRY_RUN").unwrap_or(defaults.dry_run),
+            max_concurrent_calls: env_value("TODO_MAX_CONCURRENT_CALLS")
+                .unwrap_or(defaults.max_concurrent_calls),
+            call_log_path: env::var_os("TODO_CALL_LOG")
+                .map(PathBuf::from)
+                .or(defaults.call_log_path),
+            call_log_redact: env::var("TODO_CALL_LOG_REDACT")
+                .map(|names| {
+                    names
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or(defaults.call_log_redact),
+            max_title_chars: env_value("TODO_MAX_TITLE_CHARS")
+                .map(|max_chars: usize| Some(max_chars).filter(|&max_chars| max_chars > 0))
+                .unwrap_or(defaults.max_title_chars),
+            deduplicate_on_create: env_flag("TODO_DEDUPLICATE_ON_CREATE")
+                .unwrap_or(defaults.deduplicate_on_create),
+            list_default_limit: env_value("TODO_LIST_DEFAULT_LIMIT")
+                .map(|limit: usize| Some(limit).filter(|&limit| limit > 0))
+                .unwrap_or(defaults.list_default_limit),
+            export_dir: env::var_os("TODO_EXPORT_DIR")
+                .map(PathBuf::from)
+                .or(defaults.export_dir),
+        }
+    }
+
+    /// Names of the optional features enabled by this configuration
+    pub fn enabled_features(&self) -> Vec<&'static str> {
+        let mut features = Vec::new();
+        if self.resources {
+            features.push("resources");
+        }
+        if self.dry_run {
+            features.push("dry_run");
+        }
+        if self.webhook_url.is_some() {
+            features.push("completion_webhook");
+        }
+        if self.export_dir.is_some() {
+            features.push("export_to_file");
+        }
+        features
+    }
+}
+
+/// Read a boolean environment variable such as `1`, `true` or `off`
+fn env_flag(name: &str) -> Option<bool> {
+    let value = env::var(name).ok()?;
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => {
+            tracing::warn!("Ignoring invalid value {:?} for {}", value, name);
+            None
+        },
+    }
+}
+
+/// Parse an environment variable into any `FromStr` type
+fn env_value<T: FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            tracing::warn!("Ignoring invalid value {:?} for {}", value, name);
+            None
+        },
+    }
+}
@@ -0,0 +1,76 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::todo::TodoItem;
+
+/// Inline stylesheet so the exported page needs no external assets
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; max-width: 40em; margin: 2em auto; color: #222; }
+ul { list-style: none; padding: 0; }
+li { padding: 0.5em 0; border-bottom: 1px solid #eee; }
+li .title::before { content: \"\\2610\\00a0\"; }
+li.completed .title { text-decoration: line-through; color: #888; }
+li.completed .title::before { content: \"\\2611\\00a0\"; }
+li.paused .title { font-style: italic; }
+.description { margin: 0.25em 0 0 1.5em; color: #555; white-space: pre-wrap; }
+";
+
+/// Escape text for safe inclusion in HTML element content or attributes
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render todo items as a self-contained, read-only HTML checklist
+pub fn render_html(todos: &[TodoItem]) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Todo List</title>\n<style>\n");
+    html.push_str(HTML_STYLE);
+    html.push_str("</style>\n</head>\n<body>\n<h1>Todo List</h1>\n<ul>\n");
+
+    for todo in todos {
+        let mut classes = Vec::new();
+        if todo.completed {
+            classes.push("completed");
+        }
+        if todo.paused {
+            classes.push("paused");
+        }
+
+        html.push_str(&format!(
+            "<li id=\"todo-{}\" class=\"{}\">\n<div class=\"title\">{}</div>\n",
+            escape_html(&todo.id),
+            classes.join(" "),
+            escape_html(&todo.title),
+        ));
+        if let Some(description) = &todo.description {
+            html.push_str(&format!("<div class=\"description\">{}</div>\n", escape_html(description)));
+        }
+        html.push_str("</li>\n");
+    }
+
+    html.push_str("</ul>\n</body>\n</html>\n");
+    html
+}
+
+/// Resolve a client-supplied export path inside `dir`
+///
+/// Only plain relative paths are accepted; absolute paths and `.`/`..`
+/// components are rejected so an export can never land outside `dir`.
+pub fn resolve_export_path(dir: &Path, path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path);
+    let mut components = relative.components().peekable();
+    components.peek()?;
+    if !components.all(|component| matches!(component, Component::Normal(_))) {
+        return None;
+    }
+    Some(dir.join(relative))
+}
@@ -1,7 +1,14 @@
+mod call_log;
+mod config;
+mod export;
 mod todo;
+mod webhook;
 
-use anyhow::Result;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
 use rmcp::{ServiceExt, transport::stdio};
+use config::Config;
 use todo::TodoList;
 use tracing_subscriber::{self, EnvFilter};
 
@@ -18,8 +25,38 @@ async fn main() -> Result<()> {
 
     tracing::info!("Starting MCP Todo Server...");
 
+    let mut config = Config::from_env();
+    let mut replay_path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => config.dry_run = true,
+            "--replay" => {
+                let path = args.next().context("--replay requires a call log path")?;
+                replay_path = Some(PathBuf::from(path));
+            },
+            _ => tracing::warn!("Ignoring unknown argument: {}", arg),
+        }
+    }
+    if config.dry_run {
+        tracing::info!("Dry-run mode: tool calls will not change any state");
+    }
+
     // Create TodoList service instance
-    let service = TodoList::new().serve(stdio()).await?;
+    let todo_list = TodoList::new(config);
+
+    // Rebuild state from a recorded session before accepting requests
+    if let Some(path) = replay_path {
+        let records = call_log::read_records(&path)
+            .with_context(|| format!("Failed to read call log {}", path.display()))?;
+        let applied = todo_list
+            .replay(records)
+            .await
+            .map_err(|e| anyhow::anyhow!("Replay of {} failed: {}", path.display(), e.message))?;
+        tracing::info!("Replayed {} calls from {}", applied, path.display());
+    }
+
+    let service = todo_list.serve(stdio()).await?;
 
     // Wait for service to stop
     tracing::info!("Service started, waiting for requests...");
@@ -1,15 +1,58 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rand::{SeedableRng, rngs::StdRng};
 use rmcp::{
-    Error as McpError, RoleServer, ServerHandler, model::*, 
-    service::RequestContext, tool,
+    Error as McpError, Peer, RoleServer, ServerHandler, handler::server::tool::ToolCallContext,
+    model::*,
+    service::{AtomicU32RequestIdProvider, RequestContext},
+    tool,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use tokio::sync::Mutex;
+use serde_json::{Value, json};
+use tokio::sync::{Mutex, Semaphore};
 use uuid::Uuid;
 
+use crate::call_log::{CallLog, CallRecord, CallStatus};
+use crate::config::Config;
+use crate::export;
+use crate::webhook::Webhook;
+
+/// URI scheme under which todo items are exposed as resources
+const RESOURCE_URI_PREFIX: &str = "todo://";
+
+/// MIME type of todo item resources
+const JSON_MIME_TYPE: &str = "application/json";
+
+/// Most distinct `list_todos` responses cached between mutations
+const LIST_CACHE_CAPACITY: usize = 64;
+
+/// Tools whose result is a newly created item
+const CREATING_TOOLS: &[&str] = &["create_todo", "create_from_template"];
+
+/// Tools that never change the store
+///
+/// Dry-run mode runs these against the live store instead of a scratch copy,
+/// so any other state they keep (the last-viewed markers, the export file)
+/// must only be written outside dry-run mode.
+const READ_ONLY_TOOLS: &[&str] = &[
+    "average_rating",
+    "export_html",
+    "export_to_file",
+    "get_todo",
+    "list_by_author",
+    "list_new_since_last_view",
+    "list_paused",
+    "list_todos",
+    "list_tool_schemas",
+    "validate_create",
+    "validate_update",
+];
+
+/// Confirmation string `reset` requires before it wipes the store
+const RESET_CONFIRMATION: &str = "DELETE ALL TODOS";
+
 /// Todo item structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TodoItem {
@@ -17,8 +60,162 @@ pub struct TodoItem {
     pub title: String,
     pub description: Option<String>,
     pub completed: bool,
+    pub paused: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub work_sessions: Vec<WorkSession>,
+    pub actual_minutes: Option<i64>,
+    /// Name of the client that created the item, when it identified itself
+    pub created_by: Option<String>,
+    /// Name of the client that completed the item, when it identified itself
+    pub completed_by: Option<String>,
+    /// Name of the client that last changed the item, when it identified itself
+    pub updated_by: Option<String>,
+    /// Rating from 1 to 5 given when the item was completed
+    pub rating: Option<u8>,
+    /// Free-form feedback given when the item was completed
+    pub feedback: Option<String>,
+}
+
+/// One stretch of work on a todo item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkSession {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl TodoItem {
+    fn new(title: String, description: Option<String>, created_by: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title,
+            description,
+            completed: false,
+            paused: false,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            work_sessions: Vec::new(),
+            actual_minutes: None,
+            updated_by: created_by.clone(),
+            created_by,
+            completed_by: None,
+            rating: None,
+            feedback: None,
+        }
+    }
+
+    /// Mark the item as done at `now`, taking it off hold and ending any
+    /// running work session
+    fn mark_completed(&mut self, now: DateTime<Utc>) {
+        self.completed = true;
+        self.paused = false;
+        self.stop_session(now);
+    }
+
+    /// Turn a completed item back into a pending one
+    fn reopen(&mut self) {
+        self.completed = false;
+        self.completed_at = None;
+        self.completed_by = None;
+        self.rating = None;
+        self.feedback = None;
+    }
+
+    /// Mark the item as changed at `now` by `updated_by`
+    fn touch(&mut self, now: DateTime<Utc>, updated_by: Option<String>) {
+        self.updated_at = now;
+        self.updated_by = updated_by;
+    }
+
+    fn is_running(&self) -> bool {
+        self.work_sessions.last().is_some_and(|session| session.ended_at.is_none())
+    }
+
+    /// End the running work session, if any, and re-total `actual_minutes`
+    ///
+    /// Returns whether a session was running.
+    fn stop_session(&mut self, now: DateTime<Utc>) -> bool {
+        match self.work_sessions.last_mut() {
+            Some(session) if session.ended_at.is_none() => session.ended_at = Some(now),
+            _ => return false,
+        }
+
+        let total_seconds: i64 = self
+            .work_sessions
+            .iter()
+            .filter_map(|session| Some((session.ended_at? - session.started_at).num_seconds()))
+            .sum();
+        self.actual_minutes = Some(total_seconds / 60);
+        true
+    }
+}
+
+/// Reusable blueprint for creating similar todos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoTemplate {
+    pub name: String,
+    pub title_pattern: String,
+    pub description: Option<String>,
+}
+
+/// Inverse of the most recent mutation, applied by `undo`
+#[derive(Debug, Clone)]
+enum UndoAction {
+    /// Remove an item that was created
+    Remove { id: String },
+    /// Re-insert a deleted item at its original position
+    Reinsert { index: usize, item: TodoItem },
+    /// Put back an item's state from before it was modified
+    Restore { item: TodoItem },
+    /// Put back the state of several items changed together
+    RestoreAll { items: Vec<TodoItem> },
+    /// Move an item back to its previous position
+    Reposition { id: String, index: usize },
+}
+
+/// Consecutive days, ending today or yesterday, with at least one completion
+///
+/// Derived from the completed items' `completed_at` rather than kept as
+/// separate state, so undo, reopening and resetting can never leave it out of
+/// step with the items.
+fn completion_streak(todos: &[TodoItem], today: NaiveDate) -> u32 {
+    let days: BTreeSet<NaiveDate> = todos
+        .iter()
+        .filter(|t| t.completed)
+        .filter_map(|t| t.completed_at)
+        .map(|completed_at| completed_at.date_naive())
+        .collect();
+
+    // A streak stays alive until a whole day passes without a completion
+    let mut day = if days.contains(&today) { Some(today) } else { today.pred_opt() };
+    let mut streak = 0;
+    while let Some(current) = day.filter(|d| days.contains(d)) {
+        streak += 1;
+        day = current.pred_opt();
+    }
+    streak
+}
+
+/// Request parameters for listing Todos
+#[derive(Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ListTodosRequest {
+    #[schemars(description = "Also return store_total/completed/paused/pending counts for the whole store and the completion streak")]
+    pub include_stats: Option<bool>,
+    #[schemars(description = "Flag each item with recently_changed when it was updated after this time (RFC 3339); no items are filtered out")]
+    pub changed_since: Option<DateTime<Utc>>,
+    #[schemars(description = "Also list paused items, which are hidden by default")]
+    pub include_paused: Option<bool>,
+    #[schemars(description = "Maximum number of items to return; defaults to the server's list limit, if one is configured")]
+    pub limit: Option<usize>,
+    #[schemars(description = "Return every matching item, ignoring the default list limit")]
+    pub all: Option<bool>,
+    #[schemars(description = "Return a random subset of this many matching items, in list order, instead of the whole list; cannot be combined with limit or all")]
+    pub sample: Option<usize>,
+    #[schemars(description = "Seed for sample, so the same seed picks the same items")]
+    pub seed: Option<u64>,
 }
 
 /// Request parameters for creating a new Todo
@@ -37,130 +234,465 @@ pub struct UpdateTodoRequest {
     pub completed: Option<bool>,
 }
 
+/// Request parameters for changing the same fields on several Todos
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct BulkUpdateRequest {
+    #[schemars(description = "IDs of the todo items to change")]
+    pub ids: Vec<String>,
+    #[schemars(description = "Fields to change: title, description, completed. Unlisted fields are left untouched")]
+    pub fields: Vec<String>,
+    #[serde(default)]
+    #[schemars(description = "New values keyed by field name; a listed field that is null or absent here is cleared")]
+    pub values: JsonObject,
+}
+
+/// One field assignment taken from a `bulk_update` field mask
+#[derive(Debug, Clone)]
+enum FieldChange {
+    Title(String),
+    Description(Option<String>),
+    Completed(bool),
+}
+
+impl FieldChange {
+    /// Parse the masked fields, collecting every problem rather than the first
+    fn parse_all(fields: &[String], values: &JsonObject, max_title_chars: Option<usize>) -> Result<Vec<Self>, Vec<String>> {
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+
+        for field in fields {
+            let value = values.get(field).unwrap_or(&Value::Null);
+            match (field.as_str(), value) {
+                ("title", Value::String(title)) => {
+                    let title_problems = title_errors(title, max_title_chars);
+                    if title_problems.is_empty() {
+                        changes.push(Self::Title(title.clone()));
+                    } else {
+                        errors.extend(title_problems);
+                    }
+                },
+                ("title", _) => errors.push("title must be a string and cannot be cleared".to_string()),
+                ("description", Value::String(description)) => changes.push(Self::Description(Some(description.clone()))),
+                ("description", Value::Null) => changes.push(Self::Description(None)),
+                ("description", _) => errors.push("description must be a string or null".to_string()),
+                ("completed", Value::Bool(completed)) => changes.push(Self::Completed(*completed)),
+                ("completed", _) => errors.push("completed must be a boolean and cannot be cleared".to_string()),
+                (other, _) => errors.push(format!("unknown field {:?}", other)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(changes)
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn apply(&self, todo: &mut TodoItem, now: DateTime<Utc>) {
+        match self {
+            Self::Title(title) => todo.title = title.clone(),
+            Self::Description(description) => todo.description = description.clone(),
+            Self::Completed(true) => todo.mark_completed(now),
+            Self::Completed(false) => todo.reopen(),
+        }
+    }
+}
+
+/// Request parameters for defining a todo template
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateTemplateRequest {
+    #[schemars(description = "Template name")]
+    pub name: String,
+    #[schemars(description = "Title with {var} placeholders, e.g. \"Code review for PR #{pr}\"")]
+    pub title_pattern: String,
+    #[schemars(description = "Description, may also contain {var} placeholders")]
+    pub description: Option<String>,
+}
+
+/// Request parameters for creating a Todo from a template
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateFromTemplateRequest {
+    #[schemars(description = "Template name")]
+    pub name: String,
+    #[serde(default)]
+    #[schemars(description = "Values for the template's {var} placeholders")]
+    pub vars: HashMap<String, String>,
+}
+
+/// Request parameters for clearing the store
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ResetRequest {
+    #[schemars(description = "Must be exactly \"DELETE ALL TODOS\" to confirm")]
+    pub confirm: String,
+    #[schemars(description = "Also remove all templates")]
+    pub include_templates: Option<bool>,
+}
+
+/// Request parameters for completing a Todo with a retrospective rating
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CompleteWithFeedbackRequest {
+    #[schemars(description = "Todo item ID")]
+    pub id: String,
+    #[schemars(description = "How it went, from 1 (poorly) to 5 (very well)")]
+    pub rating: Option<u8>,
+    #[schemars(description = "Short free-form retrospective note")]
+    pub feedback: Option<String>,
+}
+
+/// Problems with a todo title; empty if the title is acceptable
+///
+/// Titles longer than `max_chars` are only rejected when a limit is configured.
+fn title_errors(title: &str, max_chars: Option<usize>) -> Vec<String> {
+    let mut errors = Vec::new();
+    if title.trim().is_empty() {
+        errors.push("title must not be empty".to_string());
+    }
+    let chars = title.chars().count();
+    if let Some(max_chars) = max_chars.filter(|&max_chars| chars > max_chars) {
+        errors.push(format!("title is {} characters long, the maximum is {}", chars, max_chars));
+    }
+    errors
+}
+
+/// Title reduced to a form where near-identical titles compare equal
+fn normalize_title(title: &str) -> String {
+    title.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Error returned when a request fails validation
+fn validation_failed(errors: Vec<String>) -> McpError {
+    McpError::invalid_params("Invalid todo item", Some(json!({"errors": errors})))
+}
+
+impl CreateTodoRequest {
+    /// Everything `create_todo` would reject about this request
+    fn validation_errors(&self, max_title_chars: Option<usize>) -> Vec<String> {
+        title_errors(&self.title, max_title_chars)
+    }
+}
+
+impl UpdateTodoRequest {
+    /// Everything `update_todo` would reject about the new field values
+    fn validation_errors(&self, max_title_chars: Option<usize>) -> Vec<String> {
+        self.title
+            .as_deref()
+            .map(|title| title_errors(title, max_title_chars))
+            .unwrap_or_default()
+    }
+}
+
 /// TodoList service
 #[derive(Clone)]
 pub struct TodoList {
+    config: Arc<Config>,
+    /// Identity of the client whose tool call this handle is serving
+    caller: Option<String>,
     todos: Arc<Mutex<Vec<TodoItem>>>,
+    templates: Arc<Mutex<HashMap<String, TodoTemplate>>>,
+    last_undo: Arc<Mutex<Option<UndoAction>>>,
+    /// Serialized `list_todos` responses keyed by their request parameters
+    list_cache: Arc<Mutex<HashMap<String, String>>>,
+    /// When each client last listed the todos
+    last_viewed: Arc<Mutex<HashMap<Option<String>, DateTime<Utc>>>>,
+    webhook: Option<Webhook>,
+    call_permits: Arc<Semaphore>,
+    call_log: Option<Arc<CallLog>>,
+}
+
+/// Name the client gave when it initialized the session, if any
+fn client_identity(context: &RequestContext<RoleServer>) -> Option<String> {
+    let name = context.peer.peer_info().client_info.name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// First clause of a tool description, for the one-line summaries in the instructions
+fn tool_summary(description: &str) -> &str {
+    description
+        .split([';', ':', '.'])
+        .next()
+        .unwrap_or(description)
+        .trim()
+}
+
+/// Request context for tool calls that don't come from a connected client
+///
+/// Replayed calls run on behalf of their recorded caller, so the peer here
+/// is never consulted.
+fn detached_context() -> RequestContext<RoleServer> {
+    let (peer, _outbound) = Peer::new(Arc::new(AtomicU32RequestIdProvider::default()), ClientInfo::default());
+    RequestContext {
+        ct: Default::default(),
+        id: RequestId::Number(0),
+        peer,
+    }
+}
+
+/// ID of the item a creating tool's result newly created
+///
+/// Results that handed back an existing item (see `deduplicate_on_create`)
+/// created nothing and yield `None`.
+fn created_item_id(result: &CallToolResult) -> Option<String> {
+    let text = &result.content.first()?.as_text()?.text;
+    let item: Value = serde_json::from_str(text).ok()?;
+    if item.get("deduplicated").and_then(Value::as_bool) == Some(true) {
+        return None;
+    }
+    item.get("id")?.as_str().map(str::to_string)
+}
+
+/// Substitute `{var}` placeholders in a template pattern
+///
+/// On failure returns the names of the placeholders that have no value.
+fn render_template(pattern: &str, vars: &HashMap<String, String>) -> Result<String, Vec<String>> {
+    let mut rendered = String::with_capacity(pattern.len());
+    let mut missing = Vec::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match vars.get(name) {
+                    Some(value) => rendered.push_str(value),
+                    None => missing.push(name.to_string()),
+                }
+                rest = &after[end + 1..];
+            },
+            None => {
+                rendered.push_str(&rest[start..]);
+                rest = "";
+            },
+        }
+    }
+    rendered.push_str(rest);
+
+    if missing.is_empty() {
+        Ok(rendered)
+    } else {
+        Err(missing)
+    }
 }
 
 #[tool(tool_box)]
 impl TodoList {
-    pub fn new() -> Self {
+    pub fn new(config: Config) -> Self {
+        let webhook = config.webhook_url.clone().map(Webhook::new);
+        let call_permits = Arc::new(Semaphore::new(config.max_concurrent_calls.max(1)));
+        let call_log = config.call_log_path.as_ref().and_then(|path| {
+            match CallLog::open(path, config.call_log_redact.clone()) {
+                Ok(call_log) => Some(Arc::new(call_log)),
+                Err(e) => {
+                    tracing::error!("Failed to open call log {}: {}", path.display(), e);
+                    None
+                },
+            }
+        });
         Self {
+            config: Arc::new(config),
+            caller: None,
+            webhook,
+            call_permits,
+            call_log,
             todos: Arc::new(Mutex::new(Vec::new())),
+            templates: Arc::new(Mutex::new(HashMap::new())),
+            last_undo: Arc::new(Mutex::new(None)),
+            list_cache: Arc::new(Mutex::new(HashMap::new())),
+            last_viewed: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
-    /// List all todo items
-    #[tool(description = "List all todo items")]
-    async fn list_todos(&self) -> Result<CallToolResult, McpError> {
-        let todos = self.todos.lock().await;
-        let todos_json = serde_json::to_string_pretty(&*todos)
-            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
-        
-        Ok(CallToolResult::success(vec![Content::text(todos_json)]))
+    /// Every registered tool, ordered by name
+    fn sorted_tools() -> Vec<Tool> {
+        let mut tools = Self::tool_box().list();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
     }
 
-    /// Create a new todo item
-    #[tool(description = "Create a new todo item")]
-    async fn create_todo(
-        &self,
-        #[tool(aggr)] req: CreateTodoRequest,
-    ) -> Result<CallToolResult, McpError> {
-        let now = Utc::now();
-        let todo = TodoItem {
-            id: Uuid::new_v4().to_string(),
-            title: req.title,
-            description: req.description,
-            completed: false,
-            created_at: now,
-            updated_at: now,
-        };
+    /// Handle on the same store that attributes changes to `caller`
+    fn with_caller(&self, caller: Option<String>) -> Self {
+        Self {
+            caller,
+            ..self.clone()
+        }
+    }
 
-        let mut todos = self.todos.lock().await;
-        todos.push(todo.clone());
+    /// Deep copy of the service whose mutations never reach this one
+    ///
+    /// Used to simulate mutating tool calls in dry-run mode; the copy has no
+    /// webhook.
+    async fn scratch_copy(&self) -> Self {
+        let todos = self.todos.lock().await.clone();
+        let templates = self.templates.lock().await.clone();
+        let last_undo = self.last_undo.lock().await.clone();
+        let last_viewed = self.last_viewed.lock().await.clone();
+        Self {
+            config: self.config.clone(),
+            caller: self.caller.clone(),
+            webhook: None,
+            call_permits: self.call_permits.clone(),
+            call_log: None,
+            todos: Arc::new(Mutex::new(todos)),
+            templates: Arc::new(Mutex::new(templates)),
+            last_undo: Arc::new(Mutex::new(last_undo)),
+            list_cache: Arc::new(Mutex::new(HashMap::new())),
+            last_viewed: Arc::new(Mutex::new(last_viewed)),
+        }
+    }
 
-        let todo_json = serde_json::to_string_pretty(&todo)
-            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
-        
-        Ok(CallToolResult::success(vec![Content::text(todo_json)]))
+    /// Re-execute a recorded call log against this store
+    ///
+    /// Successful calls to every tool except the read-only ones are sent
+    /// through the tool router, in order, on behalf of the client recorded
+    /// for each, without firing the completion webhook. Items created during
+    /// replay take over their recorded IDs so later calls in the log still
+    /// find them; timestamps reflect the time of the replay. Records naming an unknown tool or carrying redacted
+    /// arguments abort the replay, since applying them would diverge from
+    /// the recorded session.
+    /// Returns the number of calls applied.
+    pub async fn replay(&self, records: Vec<CallRecord>) -> Result<usize, McpError> {
+        let tools = Self::tool_box().list();
+        let mut applied = 0;
+        for (index, record) in records.into_iter().enumerate() {
+            if record.status != CallStatus::Ok || READ_ONLY_TOOLS.contains(&record.tool.as_str()) {
+                continue;
+            }
+            if !tools.iter().any(|tool| tool.name == record.tool) {
+                return Err(McpError::invalid_params(
+                    "Call log record names an unknown tool",
+                    Some(json!({"record": index + 1, "tool": record.tool})),
+                ));
+            }
+            if record.has_redacted_arguments() {
+                return Err(McpError::invalid_params(
+                    "Call log record has redacted arguments and cannot be replayed",
+                    Some(json!({"record": index + 1, "tool": record.tool})),
+                ));
+            }
+
+            // Past completions were already announced; don't send them again
+            let service = Self {
+                webhook: None,
+                ..self.with_caller(record.caller)
+            };
+            let request = CallToolRequestParam {
+                name: record.tool.into(),
+                arguments: record.arguments,
+            };
+            let result = Self::tool_box()
+                .call(ToolCallContext::new(&service, request, detached_context()))
+                .await?;
+
+            if let (Some(created_id), Some(recorded_id)) = (created_item_id(&result), record.created_id) {
+                self.adopt_recorded_id(&created_id, recorded_id).await;
+            }
+            applied += 1;
+        }
+        Ok(applied)
     }
 
-    /// Update a todo item
-    #[tool(description = "Update a todo item")]
-    async fn update_todo(
-        &self,
-        #[tool(aggr)] req: UpdateTodoRequest,
-    ) -> Result<CallToolResult, McpError> {
+    /// Remember how to undo a mutation of the todo list
+    ///
+    /// Every mutation goes through here (or `invalidate_list_cache`) so
+    /// that cached `list_todos` responses never outlive the data they show.
+    async fn record_change(&self, undo: UndoAction) {
+        *self.last_undo.lock().await = Some(undo);
+        self.invalidate_list_cache().await;
+    }
+
+    async fn invalidate_list_cache(&self) {
+        self.list_cache.lock().await.clear();
+    }
+
+    /// Give a replayed item the ID it had when the call was recorded
+    async fn adopt_recorded_id(&self, created_id: &str, recorded_id: String) {
         let mut todos = self.todos.lock().await;
-        let todo = todos.iter_mut().find(|t| t.id == req.id);
-        
-        match todo {
-            Some(todo) => {
-                if let Some(title) = req.title {
-                    todo.title = title;
-                }
-                if let Some(description) = req.description {
-                    todo.description = Some(description);
-                }
-                if let Some(completed) = req.completed {
-                    todo.completed = completed;
-                }
-                todo.updated_at = Utc::now();
+        if let Some(todo) = todos.iter_mut().find(|t| t.id == created_id) {
+            todo.id = recorded_id.clone();
+        }
+        if let Some(UndoAction::Remove { id: undo_id }) = self.last_undo.lock().await.as_mut() {
+            if undo_id == created_id {
+                *undo_id = recorded_id;
+            }
+        }
+        self.invalidate_list_cache().await;
+    }
 
-                let todo_json = serde_json::to_string_pretty(&todo)
-                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
-                
-                Ok(CallToolResult::success(vec![Content::text(todo_json)]))
-            },
-            None => Err(McpError::invalid_params(
-                "Todo item with specified ID not found",
-                Some(json!({"id": req.id})),
-            )),
+    /// Handle an item that just became completed
+    ///
+    /// Stamps completed_at (which the completion streak is derived from),
+    /// attributes the completion to the caller and schedules the webhook, if
+    /// one is configured.
+    fn record_completion(&self, item: &mut TodoItem) {
+        item.completed_at = Some(item.updated_at);
+        item.completed_by = self.caller.clone();
+        if let Some(webhook) = &self.webhook {
+            self.notify_after_grace_period(webhook.clone(), item);
         }
     }
 
-    /// Delete a todo item
-    #[tool(description = "Delete a todo item")]
-    async fn delete_todo(
+    /// Fire the completion webhook once the grace period has passed
+    ///
+    /// If the item was reopened (or reopened and completed again) in the
+    /// meantime, this completion is dropped so `uncomplete_todo` leaves no trace.
+    fn notify_after_grace_period(&self, webhook: Webhook, item: &TodoItem) {
+        let todos = self.todos.clone();
+        let grace_period = self.config.completion_grace_period;
+        let id = item.id.clone();
+        let completed_at = item.completed_at;
+        tokio::spawn(async move {
+            tokio::time::sleep(grace_period).await;
+            let todos = todos.lock().await;
+            if let Some(item) = todos.iter().find(|t| t.id == id && t.completed && t.completed_at == completed_at) {
+                webhook.notify_completed(item);
+            }
+        });
+    }
+
+    /// Mark an item as completed, storing the rating and feedback if given
+    async fn complete_item(
         &self,
-        #[tool(param)]
-        #[schemars(description = "Todo item ID")]
         id: String,
+        rating: Option<u8>,
+        feedback: Option<String>,
     ) -> Result<CallToolResult, McpError> {
         let mut todos = self.todos.lock().await;
-        let index = todos.iter().position(|t| t.id == id);
-        
-        match index {
-            Some(idx) => {
-                todos.remove(idx);
-                Ok(CallToolResult::success(vec![Content::text(
-                    format!("Successfully deleted todo item with ID {}", id)
-                )]))
-            },
-            None => Err(McpError::invalid_params(
-                "Todo item with specified ID not found",
-                Some(json!({"id": id})),
-            )),
-        }
+        self.complete_locked(&mut todos, &id, rating, feedback).await
     }
 
-    /// Get details of a single todo item
-    #[tool(description = "Get details of a single todo item")]
-    async fn get_todo(
+    /// `complete_item` for callers that already hold the todo list lock
+    async fn complete_locked(
         &self,
-        #[tool(param)]
-        #[schemars(description = "Todo item ID")]
-        id: String,
+        todos: &mut [TodoItem],
+        id: &str,
+        rating: Option<u8>,
+        feedback: Option<String>,
     ) -> Result<CallToolResult, McpError> {
-        let todos = self.todos.lock().await;
-        let todo = todos.iter().find(|t| t.id == id);
-        
+        let todo = todos.iter_mut().find(|t| t.id == id);
+
         match todo {
             Some(todo) => {
-                let todo_json = serde_json::to_string_pretty(todo)
+                let previous = todo.clone();
+                let now = Utc::now();
+                todo.mark_completed(now);
+                if rating.is_some() {
+                    todo.rating = rating;
+                }
+                if feedback.is_some() {
+                    todo.feedback = feedback;
+                }
+                todo.touch(now, self.caller.clone());
+                if !previous.completed {
+                    self.record_completion(todo);
+                }
+                let mut item = json!(todo);
+                item["completion_streak"] = json!(completion_streak(todos, now.date_naive()));
+                self.record_change(UndoAction::Restore { item: previous }).await;
+
+                let todo_json = serde_json::to_string_pretty(&item)
                     .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
-                
+
                 Ok(CallToolResult::success(vec![Content::text(todo_json)]))
             },
             None => Err(McpError::invalid_params(
@@ -170,25 +702,29 @@ impl TodoList {
         }
     }
 
-    /// Mark a todo item as completed
-    #[tool(description = "Mark a todo item as completed")]
-    async fn complete_todo(
-        &self,
-        #[tool(param)]
-        #[schemars(description = "Todo item ID")]
-        id: String,
-    ) -> Result<CallToolResult, McpError> {
+    /// Put a pending item on hold, or take it off hold
+    async fn set_paused(&self, id: String, paused: bool) -> Result<CallToolResult, McpError> {
         let mut todos = self.todos.lock().await;
         let todo = todos.iter_mut().find(|t| t.id == id);
-        
+
         match todo {
+            Some(todo) if todo.completed => Err(McpError::invalid_params(
+                "Todo item is already completed",
+                Some(json!({"id": id})),
+            )),
             Some(todo) => {
-                todo.completed = true;
-                todo.updated_at = Utc::now();
+                let previous = todo.clone();
+                let now = Utc::now();
+                if paused {
+                    todo.stop_session(now);
+                }
+                todo.paused = paused;
+                todo.touch(now, self.caller.clone());
+                self.record_change(UndoAction::Restore { item: previous }).await;
 
                 let todo_json = serde_json::to_string_pretty(&todo)
                     .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
-                
+
                 Ok(CallToolResult::success(vec![Content::text(todo_json)]))
             },
             None => Err(McpError::invalid_params(
@@ -197,26 +733,2345 @@ impl TodoList {
             )),
         }
     }
-}
 
-#[tool(tool_box)]
-impl ServerHandler for TodoList {
-    fn get_info(&self) -> ServerInfo {
-        ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder()
-                .enable_tools()
-                .build(),
-            server_info: Implementation::from_build_env(),
-            instructions: Some("This is a todo server that helps you manage your todo list. Use list_todos to view all todos, create_todo to create new todos, update_todo to update existing todos, delete_todo to remove todos, get_todo to view todo details, and complete_todo to mark todos as completed.".to_string()),
-        }
+    /// Shift an item one position towards the start (`up`) or end of the list
+    async fn shift_todo(&self, id: String, up: bool) -> Result<CallToolResult, McpError> {
+        let mut todos = self.todos.lock().await;
+        let index = todos.iter().position(|t| t.id == id).ok_or_else(|| McpError::invalid_params(
+            "Todo item with specified ID not found",
+            Some(json!({"id": id})),
+        ))?;
+
+        let target = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1).filter(|&i| i < todos.len())
+        };
+
+        let message = match target {
+            Some(target) => {
+                todos.swap(index, target);
+                self.record_change(UndoAction::Reposition { id: id.clone(), index }).await;
+                format!("Moved todo item with ID {} to position {}", id, target)
+            },
+            None => format!(
+                "Todo item with ID {} is already at the {}, nothing moved",
+                id,
+                if up { "top" } else { "bottom" },
+            ),
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(message)]))
     }
 
-    async fn initialize(
+    /// List todo items, optionally capped at a limit
+    #[tool(description = "List todo items as an array. When a limit applies (the limit parameter, or a default limit configured on the server, unless all is set) or include_stats is set, the response is an object instead: items, total (the number of items the listing covers, before the limit) and truncated, plus the include_stats counts")]
+    async fn list_todos(
         &self,
-        _request: InitializeRequestParam,
-        _context: RequestContext<RoleServer>,
-    ) -> Result<InitializeResult, McpError> {
-        Ok(self.get_info())
-    }
-} 
+        #[tool(aggr)] req: ListTodosRequest,
+    ) -> Result<CallToolResult, McpError> {
+        if req.sample.is_some() && (req.limit.is_some() || req.all.is_some()) {
+            return Err(McpError::invalid_params(
+                "sample sets the number of items itself and cannot be combined with limit or all",
+                Some(json!({"sample": req.sample, "limit": req.limit, "all": req.all})),
+            ));
+        }
+
+        let todos = self.todos.lock().await;
+        let include_paused = req.include_paused.unwrap_or(false);
+        // A sample decides its own size, so no limit applies to it
+        let limit = if req.all.unwrap_or(false) || req.sample.is_some() {
+            None
+        } else {
+            req.limit.or(self.config.list_default_limit)
+        };
+        let mut visible: Vec<_> = todos
+            .iter()
+            .filter(|todo| include_paused || !todo.paused)
+            .collect();
+        let matching = visible.len();
+        let truncated = limit.is_some_and(|limit| matching > limit);
+
+        // Only a response that shows every item means the client has seen them all
+        let sampled_out = req.sample.is_some_and(|size| size < matching);
+        if matching == todos.len() && !truncated && !sampled_out && !self.config.dry_run {
+            self.last_viewed.lock().await.insert(self.caller.clone(), Utc::now());
+        }
+
+        // The streak in include_stats depends on the date, so it is part of the key
+        let cache_key = format!(
+            "{}|{}",
+            serde_json::to_string(&req).unwrap_or_default(),
+            Utc::now().date_naive(),
+        );
+        if let Some(cached) = self.list_cache.lock().await.get(&cache_key) {
+            return Ok(CallToolResult::success(vec![Content::text(cached.clone())]));
+        }
+
+        if let Some(size) = req.sample {
+            let mut rng = match req.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let mut picked = rand::seq::index::sample(&mut rng, matching, size.min(matching)).into_vec();
+            picked.sort_unstable();
+            visible = picked.into_iter().map(|index| visible[index]).collect();
+        }
+        let items: Vec<_> = visible
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|todo| {
+                let mut item = json!(todo);
+                if let Some(since) = req.changed_since {
+                    item["recently_changed"] = json!(todo.updated_at > since);
+                }
+                item
+            })
+            .collect();
+
+        let response = if req.include_stats.unwrap_or(false) {
+            let completed = todos.iter().filter(|t| t.completed).count();
+            let paused = todos.iter().filter(|t| !t.completed && t.paused).count();
+            let streak = completion_streak(&todos, Utc::now().date_naive());
+            // total counts the listed items, as in the limited shape; the rest cover the whole store
+            json!({
+                "items": items,
+                "total": matching,
+                "truncated": truncated,
+                "store_total": todos.len(),
+                "completed": completed,
+                "paused": paused,
+                "pending": todos.len() - completed - paused,
+                "completion_streak": streak,
+            })
+        } else if limit.is_some() {
+            // The shape follows from the request, never from how many items there are
+            json!({
+                "items": items,
+                "total": matching,
+                "truncated": truncated,
+            })
+        } else {
+            json!(items)
+        };
+        let todos_json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+        // An unseeded sample is meant to differ between calls
+        if req.sample.is_none() || req.seed.is_some() {
+            let mut list_cache = self.list_cache.lock().await;
+            if list_cache.len() >= LIST_CACHE_CAPACITY {
+                list_cache.clear();
+            }
+            list_cache.insert(cache_key, todos_json.clone());
+        }
+        
+        Ok(CallToolResult::success(vec![Content::text(todos_json)]))
+    }
+
+    /// List the items created or changed since this client last looked
+    #[tool(description = "List todo items created or updated since this client last saw the whole list: its previous list_todos call that returned every item (none hidden as paused, cut off by a limit or left out of a sample), or its previous call to this tool. Every item is returned on the first call")]
+    async fn list_new_since_last_view(&self) -> Result<CallToolResult, McpError> {
+        let todos = self.todos.lock().await;
+        let mut last_viewed = self.last_viewed.lock().await;
+        // Dry-run mode leaves every piece of server state untouched
+        let since = if self.config.dry_run {
+            last_viewed.get(&self.caller).copied()
+        } else {
+            last_viewed.insert(self.caller.clone(), Utc::now())
+        };
+        drop(last_viewed);
+
+        let items: Vec<_> = todos
+            .iter()
+            .filter(|todo| since.is_none_or(|since| todo.updated_at > since))
+            .collect();
+        let result = json!({
+            "since": since,
+            "items": items,
+        });
+        let result_json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result_json)]))
+    }
+
+    /// Create a new todo item
+    #[tool(description = "Create a new todo item")]
+    async fn create_todo(
+        &self,
+        #[tool(aggr)] req: CreateTodoRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let errors = req.validation_errors(self.config.max_title_chars);
+        if !errors.is_empty() {
+            return Err(validation_failed(errors));
+        }
+
+        let mut todos = self.todos.lock().await;
+        if self.config.deduplicate_on_create {
+            let title = normalize_title(&req.title);
+            if let Some(existing) = todos.iter().find(|t| !t.completed && normalize_title(&t.title) == title) {
+                let mut item = json!(existing);
+                item["deduplicated"] = json!(true);
+                let todo_json = serde_json::to_string_pretty(&item)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+                return Ok(CallToolResult::success(vec![Content::text(todo_json)]));
+            }
+        }
+
+        let todo = TodoItem::new(req.title, req.description, self.caller.clone());
+        todos.push(todo.clone());
+        self.record_change(UndoAction::Remove { id: todo.id.clone() }).await;
+
+        let todo_json = serde_json::to_string_pretty(&todo)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+        
+        Ok(CallToolResult::success(vec![Content::text(todo_json)]))
+    }
+
+    /// Change the same fields on several todo items
+    #[tool(description = "Change the listed fields on several todo items at once. Only fields named in fields are touched; a listed field that is null or missing in values is cleared. Completing items reports how many were completed and the completion_streak, like complete_todo")]
+    async fn bulk_update(
+        &self,
+        #[tool(aggr)] req: BulkUpdateRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let changes = FieldChange::parse_all(&req.fields, &req.values, self.config.max_title_chars).map_err(validation_failed)?;
+
+        let mut todos = self.todos.lock().await;
+        let missing: Vec<&String> = req
+            .ids
+            .iter()
+            .filter(|id| !todos.iter().any(|t| &t.id == *id))
+            .collect();
+        if !missing.is_empty() {
+            return Err(McpError::invalid_params(
+                "Todo items with specified IDs not found",
+                Some(json!({"ids": missing})),
+            ));
+        }
+
+        let now = Utc::now();
+        let mut previous = Vec::new();
+        let mut updated = Vec::new();
+        let mut newly_completed = 0;
+        for todo in todos.iter_mut().filter(|t| req.ids.contains(&t.id)) {
+            let before = todo.clone();
+            for change in &changes {
+                change.apply(todo, now);
+            }
+            todo.touch(now, self.caller.clone());
+            if todo.completed && !before.completed {
+                self.record_completion(todo);
+                newly_completed += 1;
+            }
+            previous.push(before);
+            updated.push(todo.clone());
+        }
+        self.record_change(UndoAction::RestoreAll { items: previous }).await;
+
+        let mut result = json!({"updated": updated.len(), "items": updated});
+        if newly_completed > 0 {
+            result["completed"] = json!(newly_completed);
+            result["completion_streak"] = json!(completion_streak(&todos, now.date_naive()));
+        }
+        let result_json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result_json)]))
+    }
+
+    /// Check a create_todo request without applying it
+    #[tool(description = "Check a create_todo request without creating anything; returns valid and a list of errors")]
+    async fn validate_create(
+        &self,
+        #[tool(aggr)] req: CreateTodoRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let errors = req.validation_errors(self.config.max_title_chars);
+        let report = json!({"valid": errors.is_empty(), "errors": errors});
+        let report_json = serde_json::to_string_pretty(&report)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(report_json)]))
+    }
+
+    /// Check an update_todo request without applying it
+    #[tool(description = "Check an update_todo request without changing anything; returns valid and a list of errors")]
+    async fn validate_update(
+        &self,
+        #[tool(aggr)] req: UpdateTodoRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let mut errors = req.validation_errors(self.config.max_title_chars);
+        if !self.todos.lock().await.iter().any(|t| t.id == req.id) {
+            errors.push(format!("no todo item with ID {}", req.id));
+        }
+        let report = json!({"valid": errors.is_empty(), "errors": errors});
+        let report_json = serde_json::to_string_pretty(&report)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(report_json)]))
+    }
+
+    /// Update a todo item
+    #[tool(description = "Update a todo item")]
+    async fn update_todo(
+        &self,
+        #[tool(aggr)] req: UpdateTodoRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let errors = req.validation_errors(self.config.max_title_chars);
+        if !errors.is_empty() {
+            return Err(validation_failed(errors));
+        }
+
+        let mut todos = self.todos.lock().await;
+        let todo = todos.iter_mut().find(|t| t.id == req.id);
+        
+        match todo {
+            Some(todo) => {
+                let previous = todo.clone();
+                let now = Utc::now();
+                if let Some(title) = req.title {
+                    todo.title = title;
+                }
+                if let Some(description) = req.description {
+                    todo.description = Some(description);
+                }
+                match req.completed {
+                    Some(true) => todo.mark_completed(now),
+                    Some(false) => todo.reopen(),
+                    None => {},
+                }
+                todo.touch(now, self.caller.clone());
+                if todo.completed && !previous.completed {
+                    self.record_completion(todo);
+                }
+                self.record_change(UndoAction::Restore { item: previous }).await;
+
+                let todo_json = serde_json::to_string_pretty(&todo)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+                
+                Ok(CallToolResult::success(vec![Content::text(todo_json)]))
+            },
+            None => Err(McpError::invalid_params(
+                "Todo item with specified ID not found",
+                Some(json!({"id": req.id})),
+            )),
+        }
+    }
+
+    /// Delete a todo item
+    #[tool(description = "Delete a todo item; the response is {message, deleted} with the deleted item's full JSON")]
+    async fn delete_todo(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        let mut todos = self.todos.lock().await;
+        let index = todos.iter().position(|t| t.id == id);
+        
+        match index {
+            Some(idx) => {
+                let item = todos.remove(idx);
+                let result = json!({
+                    "message": format!("Successfully deleted todo item with ID {}", id),
+                    "deleted": item,
+                });
+                let result_json = serde_json::to_string_pretty(&result)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+                self.record_change(UndoAction::Reinsert { index: idx, item }).await;
+
+                Ok(CallToolResult::success(vec![Content::text(result_json)]))
+            },
+            None => Err(McpError::invalid_params(
+                "Todo item with specified ID not found",
+                Some(json!({"id": id})),
+            )),
+        }
+    }
+
+    /// Get details of a single todo item
+    #[tool(description = "Get details of a single todo item, or a not_modified marker if it hasn't changed since if_updated_after")]
+    async fn get_todo(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+        #[tool(param)]
+        #[schemars(description = "Only return the full item if it was updated after this time (RFC 3339)")]
+        if_updated_after: Option<DateTime<Utc>>,
+        #[tool(param)]
+        #[schemars(description = "Include prev_id/next_id of the neighbouring items in list_todos order, skipping paused items")]
+        with_navigation: Option<bool>,
+    ) -> Result<CallToolResult, McpError> {
+        let todos = self.todos.lock().await;
+        let todo = todos.iter().find(|t| t.id == id);
+        
+        match todo {
+            Some(todo) if if_updated_after.is_some_and(|since| todo.updated_at <= since) => {
+                let marker = json!({
+                    "id": todo.id,
+                    "not_modified": true,
+                    "updated_at": todo.updated_at,
+                });
+                let marker_json = serde_json::to_string_pretty(&marker)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+                Ok(CallToolResult::success(vec![Content::text(marker_json)]))
+            },
+            Some(todo) => {
+                let mut item = json!(todo);
+                if with_navigation.unwrap_or(false) {
+                    // Step through the order list_todos shows, which hides paused items
+                    let visible: Vec<_> = todos.iter().filter(|t| !t.paused || t.id == todo.id).collect();
+                    let idx = visible.iter().position(|t| t.id == todo.id).unwrap_or_default();
+                    item["prev_id"] = json!(idx.checked_sub(1).map(|i| &visible[i].id));
+                    item["next_id"] = json!(visible.get(idx + 1).map(|t| &t.id));
+                }
+                let todo_json = serde_json::to_string_pretty(&item)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+                
+                Ok(CallToolResult::success(vec![Content::text(todo_json)]))
+            },
+            None => Err(McpError::invalid_params(
+                "Todo item with specified ID not found",
+                Some(json!({"id": id})),
+            )),
+        }
+    }
+
+    /// Mark a todo item as completed
+    #[tool(description = "Mark a todo item as completed; the response includes the current daily completion_streak")]
+    async fn complete_todo(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        self.complete_item(id, None, None).await
+    }
+
+    /// Mark a todo item as completed and store a rating and feedback on it
+    #[tool(description = "Mark a todo item as completed like complete_todo, also storing an optional 1-5 rating and feedback on the item")]
+    async fn complete_with_feedback(
+        &self,
+        #[tool(aggr)] req: CompleteWithFeedbackRequest,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(rating) = req.rating {
+            if !(1..=5).contains(&rating) {
+                return Err(McpError::invalid_params(
+                    "Rating must be between 1 and 5",
+                    Some(json!({"rating": rating})),
+                ));
+            }
+        }
+        self.complete_item(req.id, req.rating, req.feedback).await
+    }
+
+    /// Report the average rating over rated completed items
+    #[tool(description = "Average rating of completed todo items that were rated with complete_with_feedback")]
+    async fn average_rating(&self) -> Result<CallToolResult, McpError> {
+        let todos = self.todos.lock().await;
+        let ratings: Vec<u8> = todos
+            .iter()
+            .filter(|t| t.completed)
+            .filter_map(|t| t.rating)
+            .collect();
+        let average = if ratings.is_empty() {
+            None
+        } else {
+            Some(ratings.iter().map(|&r| f64::from(r)).sum::<f64>() / ratings.len() as f64)
+        };
+
+        let result = json!({
+            "average_rating": average,
+            "rated": ratings.len(),
+        });
+        let result_json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result_json)]))
+    }
+
+    /// Put a todo item on hold
+    #[tool(description = "Put a todo item on hold; paused items are hidden from list_todos until resumed")]
+    async fn pause_todo(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        self.set_paused(id, true).await
+    }
+
+    /// Take a todo item off hold
+    #[tool(description = "Take a paused todo item off hold")]
+    async fn resume_todo(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        self.set_paused(id, false).await
+    }
+
+    /// List todo items created or last changed by a client
+    #[tool(description = "List todo items created or last changed by the given client (as recorded in created_by/updated_by)")]
+    async fn list_by_author(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Client name as recorded in created_by/updated_by")]
+        author: String,
+    ) -> Result<CallToolResult, McpError> {
+        let todos = self.todos.lock().await;
+        let authored: Vec<_> = todos
+            .iter()
+            .filter(|t| {
+                t.created_by.as_deref() == Some(author.as_str())
+                    || t.updated_by.as_deref() == Some(author.as_str())
+            })
+            .collect();
+        let todos_json = serde_json::to_string_pretty(&authored)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(todos_json)]))
+    }
+
+    /// List todo items that are on hold
+    #[tool(description = "List todo items that are on hold")]
+    async fn list_paused(&self) -> Result<CallToolResult, McpError> {
+        let todos = self.todos.lock().await;
+        let paused: Vec<_> = todos.iter().filter(|t| t.paused).collect();
+        let todos_json = serde_json::to_string_pretty(&paused)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(todos_json)]))
+    }
+
+    /// Reopen a completed todo item
+    #[tool(description = "Reopen a completed todo item; it no longer counts toward the completion_streak. Within the completion grace period the completion webhook is also never sent")]
+    async fn uncomplete_todo(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        let mut todos = self.todos.lock().await;
+        let todo = todos.iter_mut().find(|t| t.id == id);
+
+        match todo {
+            Some(todo) if !todo.completed => Err(McpError::invalid_params(
+                "Todo item is not completed",
+                Some(json!({"id": id})),
+            )),
+            Some(todo) => {
+                let previous = todo.clone();
+                let now = Utc::now();
+                let within_grace_period = todo.completed_at.is_some_and(|completed_at| {
+                    now.signed_duration_since(completed_at).to_std().unwrap_or_default()
+                        < self.config.completion_grace_period
+                });
+                todo.reopen();
+                todo.touch(now, self.caller.clone());
+
+                let mut item = json!(todo);
+                item["within_grace_period"] = json!(within_grace_period);
+                item["completion_streak"] = json!(completion_streak(&todos, now.date_naive()));
+                self.record_change(UndoAction::Restore { item: previous }).await;
+
+                let todo_json = serde_json::to_string_pretty(&item)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+                Ok(CallToolResult::success(vec![Content::text(todo_json)]))
+            },
+            None => Err(McpError::invalid_params(
+                "Todo item with specified ID not found",
+                Some(json!({"id": id})),
+            )),
+        }
+    }
+
+    /// Complete the single pending todo item with the given title
+    #[tool(description = "Complete the single pending todo item whose title matches exactly; fails listing candidate IDs if there is no unique match")]
+    async fn complete_by_title(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Exact title of the todo item")]
+        title: String,
+    ) -> Result<CallToolResult, McpError> {
+        // Hold the lock from matching to completing so the match can't go stale
+        let mut todos = self.todos.lock().await;
+        let candidates: Vec<String> = todos
+            .iter()
+            .filter(|t| !t.completed && !t.paused && t.title == title)
+            .map(|t| t.id.clone())
+            .collect();
+
+        match candidates.as_slice() {
+            [id] => self.complete_locked(&mut todos, id, None, None).await,
+            [] => Err(McpError::invalid_params(
+                "No pending todo item with specified title",
+                Some(json!({"title": title})),
+            )),
+            _ => Err(McpError::invalid_params(
+                "Several pending todo items have this title; complete one by ID instead",
+                Some(json!({"title": title, "candidate_ids": candidates})),
+            )),
+        }
+    }
+
+    /// Start a work session on a todo item
+    #[tool(description = "Start a work session on a todo item; stop_todo or complete_todo ends it and adds the time to actual_minutes")]
+    async fn start_todo(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        let mut todos = self.todos.lock().await;
+        let todo = todos.iter_mut().find(|t| t.id == id);
+
+        match todo {
+            Some(todo) if todo.completed => Err(McpError::invalid_params(
+                "Todo item is already completed",
+                Some(json!({"id": id})),
+            )),
+            Some(todo) if todo.paused => Err(McpError::invalid_params(
+                "Todo item is paused; resume it first",
+                Some(json!({"id": id})),
+            )),
+            Some(todo) if todo.is_running() => Err(McpError::invalid_params(
+                "Todo item already has a running work session",
+                Some(json!({"id": id})),
+            )),
+            Some(todo) => {
+                let previous = todo.clone();
+                let now = Utc::now();
+                todo.work_sessions.push(WorkSession {
+                    started_at: now,
+                    ended_at: None,
+                });
+                todo.touch(now, self.caller.clone());
+                self.record_change(UndoAction::Restore { item: previous }).await;
+
+                let todo_json = serde_json::to_string_pretty(&todo)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+                Ok(CallToolResult::success(vec![Content::text(todo_json)]))
+            },
+            None => Err(McpError::invalid_params(
+                "Todo item with specified ID not found",
+                Some(json!({"id": id})),
+            )),
+        }
+    }
+
+    /// Stop the running work session on a todo item
+    #[tool(description = "Stop the running work session on a todo item and add its duration to actual_minutes")]
+    async fn stop_todo(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        let mut todos = self.todos.lock().await;
+        let todo = todos.iter_mut().find(|t| t.id == id);
+
+        match todo {
+            Some(todo) => {
+                let previous = todo.clone();
+                let now = Utc::now();
+                if !todo.stop_session(now) {
+                    return Err(McpError::invalid_params(
+                        "Todo item has no running work session",
+                        Some(json!({"id": id})),
+                    ));
+                }
+                todo.touch(now, self.caller.clone());
+                self.record_change(UndoAction::Restore { item: previous }).await;
+
+                let todo_json = serde_json::to_string_pretty(&todo)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+                Ok(CallToolResult::success(vec![Content::text(todo_json)]))
+            },
+            None => Err(McpError::invalid_params(
+                "Todo item with specified ID not found",
+                Some(json!({"id": id})),
+            )),
+        }
+    }
+
+    /// Move a todo item one position up
+    #[tool(description = "Move a todo item one position up in the list; does nothing if it is already first")]
+    async fn move_up(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        self.shift_todo(id, true).await
+    }
+
+    /// Move a todo item one position down
+    #[tool(description = "Move a todo item one position down in the list; does nothing if it is already last")]
+    async fn move_down(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Todo item ID")]
+        id: String,
+    ) -> Result<CallToolResult, McpError> {
+        self.shift_todo(id, false).await
+    }
+
+    /// Remove every todo item
+    #[tool(description = "Remove every todo item (and optionally all templates). Requires confirm to be exactly \"DELETE ALL TODOS\"")]
+    async fn reset(
+        &self,
+        #[tool(aggr)] req: ResetRequest,
+    ) -> Result<CallToolResult, McpError> {
+        if req.confirm != RESET_CONFIRMATION {
+            return Err(McpError::invalid_params(
+                "Reset not confirmed",
+                Some(json!({"expected_confirm": RESET_CONFIRMATION})),
+            ));
+        }
+
+        let mut todos = self.todos.lock().await;
+        let removed = todos.len();
+        todos.clear();
+        *self.last_undo.lock().await = None;
+        self.invalidate_list_cache().await;
+
+        let removed_templates = if req.include_templates.unwrap_or(false) {
+            let mut templates = self.templates.lock().await;
+            let count = templates.len();
+            templates.clear();
+            count
+        } else {
+            0
+        };
+
+        let result = json!({
+            "removed_todos": removed,
+            "removed_templates": removed_templates,
+        });
+        let result_json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result_json)]))
+    }
+
+    /// Export the todo list as an HTML page
+    #[tool(description = "Export the whole todo list as a self-contained, read-only HTML checklist")]
+    async fn export_html(&self) -> Result<CallToolResult, McpError> {
+        let todos = self.todos.lock().await;
+        Ok(CallToolResult::success(vec![Content::text(export::render_html(&todos))]))
+    }
+
+    /// Write every todo item as JSON to a file in the export directory
+    #[tool(description = "Write all todo items as a JSON array to a file in the server's export directory and return the file path and item count instead of the items")]
+    async fn export_to_file(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "File path relative to the export directory, e.g. backups/todos.json")]
+        path: String,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(export_dir) = &self.config.export_dir else {
+            return Err(McpError::invalid_request(
+                "Exporting to a file is disabled; set TODO_EXPORT_DIR to enable it",
+                None,
+            ));
+        };
+        let target = export::resolve_export_path(export_dir, &path).ok_or_else(|| {
+            McpError::invalid_params(
+                "Export path must be a relative path inside the export directory",
+                Some(json!({"path": path})),
+            )
+        })?;
+
+        // Symlinks inside the export directory must not lead back out of it
+        let parent = target.parent().unwrap_or(export_dir.as_path());
+        let not_writable = |e: std::io::Error| {
+            McpError::invalid_params(
+                "Export path is not writable",
+                Some(json!({"path": path, "error": e.to_string()})),
+            )
+        };
+        let root = tokio::fs::canonicalize(export_dir).await.map_err(not_writable)?;
+        let parent = tokio::fs::canonicalize(parent).await.map_err(not_writable)?;
+        if !parent.starts_with(&root) {
+            return Err(McpError::invalid_params(
+                "Export path must be a relative path inside the export directory",
+                Some(json!({"path": path})),
+            ));
+        }
+
+        // Writing through a symlink would follow it wherever it points
+        if let Ok(metadata) = tokio::fs::symlink_metadata(&target).await {
+            if metadata.file_type().is_symlink() {
+                return Err(McpError::invalid_params(
+                    "Export path must not be a symbolic link",
+                    Some(json!({"path": path})),
+                ));
+            }
+        }
+
+        // Serialize under the lock, but don't block other tools during the write
+        let (contents, count) = {
+            let todos = self.todos.lock().await;
+            let contents = serde_json::to_vec_pretty(&*todos)
+                .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+            (contents, todos.len())
+        };
+        if !self.config.dry_run {
+            tokio::fs::write(&target, contents).await.map_err(not_writable)?;
+        }
+
+        let mut result = json!({
+            "path": target.display().to_string(),
+            "count": count,
+        });
+        let mut content = Vec::new();
+        // Runs on the live store in dry-run mode, so it marks itself like simulated calls
+        if self.config.dry_run {
+            result["dry_run"] = json!(true);
+            content.push(Content::text("Dry run: the export file was not written"));
+        }
+        let result_json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+        content.push(Content::text(result_json));
+
+        Ok(CallToolResult::success(content))
+    }
+
+    /// List every tool with its description and input schema
+    #[tool(description = "List every tool this server provides with its description and input JSON Schema")]
+    async fn list_tool_schemas(&self) -> Result<CallToolResult, McpError> {
+        let catalog: Vec<_> = Self::sorted_tools()
+            .iter()
+            .map(|tool| json!({
+                "name": tool.name,
+                "description": tool.description,
+                "input_schema": tool.input_schema.as_ref(),
+            }))
+            .collect();
+        let catalog_json = serde_json::to_string_pretty(&catalog)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(catalog_json)]))
+    }
+
+    /// Undo the most recent change to the todo list
+    #[tool(description = "Undo the most recent create, update, delete or completion, leaving other items untouched")]
+    async fn undo(&self) -> Result<CallToolResult, McpError> {
+        let mut todos = self.todos.lock().await;
+        let action = self.last_undo.lock().await.take();
+        self.invalidate_list_cache().await;
+
+        let message = match action {
+            Some(UndoAction::Remove { id }) => {
+                todos.retain(|t| t.id != id);
+                format!("Undid creation of todo item with ID {}", id)
+            },
+            Some(UndoAction::Reinsert { index, item }) => {
+                let id = item.id.clone();
+                let idx = index.min(todos.len());
+                todos.insert(idx, item);
+                format!("Restored deleted todo item with ID {} at position {}", id, idx)
+            },
+            Some(UndoAction::Restore { item }) => {
+                let id = item.id.clone();
+                match todos.iter_mut().find(|t| t.id == id) {
+                    Some(todo) => *todo = item,
+                    None => return Err(McpError::invalid_params(
+                        "Todo item with specified ID not found",
+                        Some(json!({"id": id})),
+                    )),
+                }
+                format!("Reverted last change to todo item with ID {}", id)
+            },
+            Some(UndoAction::RestoreAll { items }) => {
+                let count = items.len();
+                for item in items {
+                    if let Some(todo) = todos.iter_mut().find(|t| t.id == item.id) {
+                        *todo = item;
+                    }
+                }
+                format!("Reverted last change to {} todo items", count)
+            },
+            Some(UndoAction::Reposition { id, index }) => {
+                let current = todos.iter().position(|t| t.id == id).ok_or_else(|| McpError::invalid_params(
+                    "Todo item with specified ID not found",
+                    Some(json!({"id": id})),
+                ))?;
+                let item = todos.remove(current);
+                let idx = index.min(todos.len());
+                todos.insert(idx, item);
+                format!("Moved todo item with ID {} back to position {}", id, idx)
+            },
+            None => return Err(McpError::invalid_request("Nothing to undo", None)),
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(message)]))
+    }
+
+    /// Define or replace a todo template
+    #[tool(description = "Define or replace a todo template whose title and description may contain {var} placeholders")]
+    async fn create_template(
+        &self,
+        #[tool(aggr)] req: CreateTemplateRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let template = TodoTemplate {
+            name: req.name,
+            title_pattern: req.title_pattern,
+            description: req.description,
+        };
+
+        let mut templates = self.templates.lock().await;
+        templates.insert(template.name.clone(), template.clone());
+
+        let template_json = serde_json::to_string_pretty(&template)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(template_json)]))
+    }
+
+    /// Create a new todo item from a template
+    #[tool(description = "Create a new todo item from a template, substituting {var} placeholders with the given vars")]
+    async fn create_from_template(
+        &self,
+        #[tool(aggr)] req: CreateFromTemplateRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let template = self.templates.lock().await.get(&req.name).cloned();
+        let template = template.ok_or_else(|| McpError::invalid_params(
+            "Template with specified name not found",
+            Some(json!({"name": req.name})),
+        ))?;
+
+        let mut missing = Vec::new();
+        let title = render_template(&template.title_pattern, &req.vars)
+            .unwrap_or_else(|names| {
+                missing.extend(names);
+                String::new()
+            });
+        let description = template.description.as_deref().map(|d| {
+            render_template(d, &req.vars).unwrap_or_else(|names| {
+                missing.extend(names);
+                String::new()
+            })
+        });
+        if !missing.is_empty() {
+            missing.sort();
+            missing.dedup();
+            return Err(McpError::invalid_params(
+                "Missing values for template variables",
+                Some(json!({"name": req.name, "missing": missing})),
+            ));
+        }
+
+        let errors = title_errors(&title, self.config.max_title_chars);
+        if !errors.is_empty() {
+            return Err(validation_failed(errors));
+        }
+
+        let todo = TodoItem::new(title, description, self.caller.clone());
+
+        let mut todos = self.todos.lock().await;
+        todos.push(todo.clone());
+        self.record_change(UndoAction::Remove { id: todo.id.clone() }).await;
+
+        let todo_json = serde_json::to_string_pretty(&todo)
+            .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+        Ok(CallToolResult::success(vec![Content::text(todo_json)]))
+    }
+}
+
+impl ServerHandler for TodoList {
+    fn get_info(&self) -> ServerInfo {
+        let mut capabilities = if self.config.resources {
+            ServerCapabilities::builder()
+                .enable_resources()
+                .enable_tools()
+                .build()
+        } else {
+            ServerCapabilities::builder()
+                .enable_tools()
+                .build()
+        };
+
+        let features = self.config.enabled_features();
+        let mut feature_flags = JsonObject::new();
+        feature_flags.insert("enabled".to_string(), json!(features));
+        capabilities.experimental = Some(BTreeMap::from([("todo_features".to_string(), feature_flags)]));
+
+        // One line per tool; the full descriptions and schemas are served by tools/list
+        let tool_lines: Vec<_> = Self::sorted_tools()
+            .iter()
+            .map(|tool| format!("- {}: {}", tool.name, tool_summary(&tool.description)))
+            .collect();
+        let mut instructions = format!(
+            "This is a todo server that helps you manage your todo list. Tools:\n{}\n\
+             See tools/list or the list_tool_schemas tool for their full descriptions and parameters.",
+            tool_lines.join("\n"),
+        );
+
+        let mut notes = Vec::new();
+        if self.config.resources {
+            notes.push("Each todo is also readable as a JSON resource at todo://{id}.".to_string());
+        }
+        if self.config.dry_run {
+            notes.push("The server is running in dry-run mode: every tool reports what it would do, but no changes are kept.".to_string());
+        }
+        if !features.is_empty() {
+            notes.push(format!("Enabled optional features: {}.", features.join(", ")));
+        }
+        if !notes.is_empty() {
+            instructions.push_str("\n\n");
+            instructions.push_str(&notes.join(" "));
+        }
+
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities,
+            server_info: Implementation::from_build_env(),
+            instructions: Some(instructions),
+        }
+    }
+
+    async fn initialize(
+        &self,
+        _request: InitializeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, McpError> {
+        Ok(self.get_info())
+    }
+
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: Self::tool_box().list(),
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        // Queue behind the concurrency limit instead of rejecting excess calls
+        let _permit = self.call_permits.acquire().await
+            .map_err(|e| McpError::internal_error("Tool call limiter closed", Some(json!({"error": e.to_string()}))))?;
+
+        let caller = client_identity(&context);
+        if self.config.dry_run && !READ_ONLY_TOOLS.contains(&request.name.as_ref()) {
+            let scratch = self.scratch_copy().await.with_caller(caller);
+            let mut result = Self::tool_box()
+                .call(ToolCallContext::new(&scratch, request, context))
+                .await?;
+            result.content.insert(0, Content::text("Dry run: the result below was simulated and no changes were applied"));
+            return Ok(result);
+        }
+
+        let service = self.with_caller(caller.clone());
+        let Some(call_log) = &self.call_log else {
+            return Self::tool_box()
+                .call(ToolCallContext::new(&service, request, context))
+                .await;
+        };
+
+        let tool = request.name.to_string();
+        let arguments = request.arguments.clone();
+        let result = Self::tool_box()
+            .call(ToolCallContext::new(&service, request, context))
+            .await;
+
+        let (status, created_id) = match &result {
+            Ok(result) if result.is_error != Some(true) => {
+                let created_id = if CREATING_TOOLS.contains(&tool.as_str()) {
+                    created_item_id(result)
+                } else {
+                    None
+                };
+                (CallStatus::Ok, created_id)
+            },
+            _ => (CallStatus::Error, None),
+        };
+        call_log.record(CallRecord {
+            timestamp: Utc::now(),
+            tool,
+            arguments,
+            caller,
+            status,
+            created_id,
+            redacted: Vec::new(),
+        }).await;
+
+        result
+    }
+
+    async fn list_resources(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        if !self.config.resources {
+            return Err(McpError::method_not_found::<ListResourcesRequestMethod>());
+        }
+
+        let todos = self.todos.lock().await;
+        let resources = todos
+            .iter()
+            .map(|todo| {
+                let mut resource = RawResource::new(
+                    format!("{}{}", RESOURCE_URI_PREFIX, todo.id),
+                    todo.title.clone(),
+                );
+                resource.mime_type = Some(JSON_MIME_TYPE.to_string());
+                resource.no_annotation()
+            })
+            .collect();
+
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        if !self.config.resources {
+            return Err(McpError::method_not_found::<ReadResourceRequestMethod>());
+        }
+
+        let todos = self.todos.lock().await;
+        let todo = uri
+            .strip_prefix(RESOURCE_URI_PREFIX)
+            .and_then(|id| todos.iter().find(|t| t.id == id));
+
+        match todo {
+            Some(todo) => {
+                let todo_json = serde_json::to_string_pretty(todo)
+                    .map_err(|e| McpError::internal_error("Serialization failed", Some(json!({"error": e.to_string()}))))?;
+
+                Ok(ReadResourceResult {
+                    contents: vec![ResourceContents::TextResourceContents {
+                        uri,
+                        mime_type: Some(JSON_MIME_TYPE.to_string()),
+                        text: todo_json,
+                    }],
+                })
+            },
+            None => Err(McpError::resource_not_found(
+                "Todo resource with specified URI not found",
+                Some(json!({"uri": uri})),
+            )),
+        }
+    }
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text of the first content block of a tool result
+    fn text(result: &CallToolResult) -> &str {
+        &result.content[0].as_text().expect("text content").text
+    }
+
+    /// First content block of a tool result, parsed as JSON
+    fn json_of(result: &CallToolResult) -> Value {
+        serde_json::from_str(text(result)).expect("JSON content")
+    }
+
+    /// Create a todo with `title` and return its ID
+    async fn create(list: &TodoList, title: &str) -> String {
+        let result = list
+            .create_todo(CreateTodoRequest {
+                title: title.to_string(),
+                description: None,
+            })
+            .await
+            .unwrap();
+        json_of(&result)["id"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn read_resource_uses_json_mime_type() {
+        let list = TodoList::new(Config::default());
+        let id = create(&list, "Buy milk").await;
+        let uri = format!("{}{}", RESOURCE_URI_PREFIX, id);
+
+        let result = list
+            .read_resource(ReadResourceRequestParam { uri: uri.clone() }, detached_context())
+            .await
+            .unwrap();
+
+        match &result.contents[..] {
+            [ResourceContents::TextResourceContents { uri: read_uri, mime_type, text }] => {
+                assert_eq!(read_uri, &uri);
+                assert_eq!(mime_type.as_deref(), Some(JSON_MIME_TYPE));
+                let item: Value = serde_json::from_str(text).unwrap();
+                assert_eq!(item["title"], "Buy milk");
+            },
+            other => panic!("unexpected resource contents: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn create_from_template_substitutes_variables() {
+        let list = TodoList::new(Config::default());
+        list.create_template(CreateTemplateRequest {
+            name: "review".to_string(),
+            title_pattern: "Code review for PR #{pr}".to_string(),
+            description: Some("Requested by {author}".to_string()),
+        })
+        .await
+        .unwrap();
+
+        let vars = HashMap::from([
+            ("pr".to_string(), "42".to_string()),
+            ("author".to_string(), "sam".to_string()),
+        ]);
+        let result = list
+            .create_from_template(CreateFromTemplateRequest {
+                name: "review".to_string(),
+                vars,
+            })
+            .await
+            .unwrap();
+
+        let item = json_of(&result);
+        assert_eq!(item["title"], "Code review for PR #42");
+        assert_eq!(item["description"], "Requested by sam");
+        assert_eq!(list.todos.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn create_from_template_rejects_missing_variables() {
+        let list = TodoList::new(Config::default());
+        list.create_template(CreateTemplateRequest {
+            name: "review".to_string(),
+            title_pattern: "Code review for PR #{pr}".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+
+        let error = list
+            .create_from_template(CreateFromTemplateRequest {
+                name: "review".to_string(),
+                vars: HashMap::new(),
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.data.unwrap()["missing"], json!(["pr"]));
+        assert!(list.todos.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn list_stats_cover_whole_store_when_limited() {
+        let list = TodoList::new(Config::default());
+        let first = create(&list, "First").await;
+        create(&list, "Second").await;
+        create(&list, "Third").await;
+        list.complete_todo(first).await.unwrap();
+
+        let result = list
+            .list_todos(ListTodosRequest {
+                include_stats: Some(true),
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let response = json_of(&result);
+        assert_eq!(response["items"].as_array().unwrap().len(), 1);
+        assert_eq!(response["total"], 3);
+        assert_eq!(response["completed"], 1);
+        assert_eq!(response["pending"], 2);
+        assert_eq!(response["truncated"], true);
+    }
+
+    #[tokio::test]
+    async fn undo_reinserts_deleted_item_and_keeps_unrelated_changes() {
+        let list = TodoList::new(Config::default());
+        let a = create(&list, "A").await;
+        let b = create(&list, "B").await;
+        create(&list, "C").await;
+
+        list.delete_todo(b.clone()).await.unwrap();
+        // A change made alongside the delete, outside the undo history
+        list.todos.lock().await.iter_mut().find(|t| t.id == a).unwrap().title = "A (edited)".to_string();
+
+        list.undo().await.unwrap();
+
+        let todos = list.todos.lock().await;
+        let titles: Vec<_> = todos.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["A (edited)", "B", "C"]);
+        assert_eq!(todos[1].id, b);
+    }
+
+    /// Pretend the running work session on `id` started `minutes` ago
+    async fn backdate_session(list: &TodoList, id: &str, minutes: i64) {
+        let mut todos = list.todos.lock().await;
+        let todo = todos.iter_mut().find(|t| t.id == id).unwrap();
+        let session = todo.work_sessions.last_mut().unwrap();
+        session.started_at -= chrono::Duration::minutes(minutes);
+    }
+
+    #[tokio::test]
+    async fn completing_started_item_records_elapsed_minutes() {
+        let list = TodoList::new(Config::default());
+        let id = create(&list, "Write report").await;
+        list.start_todo(id.clone()).await.unwrap();
+        backdate_session(&list, &id, 45).await;
+
+        let result = list.complete_todo(id).await.unwrap();
+
+        let item = json_of(&result);
+        assert_eq!(item["actual_minutes"], 45);
+        assert!(item["work_sessions"][0]["ended_at"].is_string());
+    }
+
+    /// Features listed under the experimental todo_features capability
+    fn advertised_features(info: &ServerInfo) -> Value {
+        let experimental = info.capabilities.experimental.as_ref().unwrap();
+        experimental["todo_features"]["enabled"].clone()
+    }
+
+    #[test]
+    fn get_info_advertises_enabled_features() {
+        let list = TodoList::new(Config::default());
+        let info = list.get_info();
+        assert!(info.capabilities.resources.is_some());
+        assert_eq!(advertised_features(&info), json!(["resources"]));
+
+        let list = TodoList::new(Config {
+            resources: false,
+            dry_run: true,
+            ..Config::default()
+        });
+        let info = list.get_info();
+        assert!(info.capabilities.resources.is_none());
+        assert_eq!(advertised_features(&info), json!(["dry_run"]));
+    }
+
+    #[tokio::test]
+    async fn tool_schema_catalog_describes_create_todo() {
+        let list = TodoList::new(Config::default());
+        let catalog = json_of(&list.list_tool_schemas().await.unwrap());
+
+        let create_todo = catalog
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|tool| tool["name"] == "create_todo")
+            .expect("create_todo in catalog");
+        assert!(create_todo["description"].is_string());
+        let required = create_todo["input_schema"]["required"].as_array().unwrap();
+        assert!(required.contains(&json!("title")));
+    }
+
+    /// Titles of all items in list order
+    async fn titles(list: &TodoList) -> Vec<String> {
+        list.todos.lock().await.iter().map(|t| t.title.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn move_up_and_down_shift_one_position() {
+        let list = TodoList::new(Config::default());
+        let a = create(&list, "A").await;
+        let b = create(&list, "B").await;
+        create(&list, "C").await;
+
+        list.move_up(b.clone()).await.unwrap();
+        assert_eq!(titles(&list).await, ["B", "A", "C"]);
+
+        list.move_down(b.clone()).await.unwrap();
+        list.move_down(b).await.unwrap();
+        assert_eq!(titles(&list).await, ["A", "C", "B"]);
+
+        let result = list.move_up(a).await.unwrap();
+        assert!(text(&result).contains("already at the top"));
+        assert_eq!(titles(&list).await, ["A", "C", "B"]);
+    }
+
+    /// Local stand-in for a webhook receiver
+    async fn webhook_listener() -> (tokio::net::TcpListener, String) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        (listener, url)
+    }
+
+    /// Accept one HTTP POST, answer 200 and return its JSON body
+    async fn receive_webhook(listener: &tokio::net::TcpListener) -> Value {
+        answer_webhook(listener, "200 OK").await
+    }
+
+    /// Accept one HTTP POST, answer with `status` and return its JSON body
+    async fn answer_webhook(listener: &tokio::net::TcpListener, status: &str) -> Value {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0; 4096];
+        loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            assert!(read > 0, "webhook connection closed before the body arrived");
+            request.extend_from_slice(&buffer[..read]);
+
+            let received = String::from_utf8_lossy(&request).into_owned();
+            let Some(header_end) = received.find("\r\n\r\n") else {
+                continue;
+            };
+            let content_length = received[..header_end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length").then(|| value.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            let body_start = header_end + 4;
+            if request.len() < body_start + content_length {
+                continue;
+            }
+
+            assert!(received.starts_with("POST /hook "));
+            let response = format!("HTTP/1.1 {}\r\ncontent-length: 0\r\n\r\n", status);
+            stream.write_all(response.as_bytes()).await.unwrap();
+            return serde_json::from_slice(&request[body_start..body_start + content_length]).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn completing_posts_item_to_webhook() {
+        let (listener, url) = webhook_listener().await;
+        let list = TodoList::new(Config {
+            webhook_url: Some(url),
+            completion_grace_period: std::time::Duration::ZERO,
+            ..Config::default()
+        });
+        let id = create(&list, "Ship release").await;
+
+        list.complete_todo(id.clone()).await.unwrap();
+
+        let body = tokio::time::timeout(std::time::Duration::from_secs(5), receive_webhook(&listener))
+            .await
+            .expect("webhook POST");
+        assert_eq!(body["id"], id);
+        assert_eq!(body["title"], "Ship release");
+        assert_eq!(body["completed"], true);
+    }
+
+    /// Invoke a tool through `call_tool`, as a connected client would
+    async fn call(
+        list: &TodoList,
+        name: &str,
+        arguments: Value,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: arguments.as_object().cloned(),
+        };
+        list.call_tool(request, context).await
+    }
+
+    #[tokio::test]
+    async fn dry_run_simulates_mutations_without_applying_them() {
+        let list = TodoList::new(Config {
+            dry_run: true,
+            ..Config::default()
+        });
+        let id = create(&list, "Existing").await;
+
+        let calls = [
+            ("create_todo", json!({"title": "New"})),
+            ("complete_todo", json!({"id": id})),
+            ("update_todo", json!({"id": id, "title": "Renamed"})),
+            ("delete_todo", json!({"id": id})),
+        ];
+        let mut simulated = Vec::new();
+        for (name, arguments) in calls {
+            let result = call(&list, name, arguments, detached_context()).await.unwrap();
+            assert!(text(&result).starts_with("Dry run"), "{} was not marked as a dry run", name);
+            let content = &result.content[1].as_text().unwrap().text;
+            simulated.push(serde_json::from_str::<Value>(content).unwrap());
+        }
+        assert_eq!(simulated[0]["title"], "New");
+        assert_eq!(simulated[1]["completed"], true);
+        assert_eq!(simulated[2]["title"], "Renamed");
+        assert_eq!(simulated[3]["deleted"]["id"], id);
+
+        // Views in dry-run mode don't move the last-viewed marker either
+        call(&list, "list_todos", json!({}), detached_context()).await.unwrap();
+        call(&list, "list_new_since_last_view", json!({}), detached_context()).await.unwrap();
+        assert!(list.last_viewed.lock().await.is_empty());
+
+        let dir = temp_path("dry-run-export");
+        std::fs::create_dir_all(&dir).unwrap();
+        let exporting = TodoList::new(Config { dry_run: true, export_dir: Some(dir.clone()), ..Config::default() });
+        let result = call(&exporting, "export_to_file", json!({"path": "todos.json"}), detached_context()).await.unwrap();
+        assert!(text(&result).starts_with("Dry run"));
+        let content = &result.content[1].as_text().unwrap().text;
+        assert_eq!(serde_json::from_str::<Value>(content).unwrap()["dry_run"], true);
+        assert!(!dir.join("todos.json").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let todos = list.todos.lock().await;
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].title, "Existing");
+        assert!(!todos[0].completed);
+    }
+
+    #[tokio::test]
+    async fn dry_run_reads_the_live_store() {
+        let list = TodoList::new(Config {
+            dry_run: true,
+            ..Config::default()
+        });
+        create(&list, "Existing").await;
+
+        let result = call(&list, "list_todos", json!({}), detached_context()).await.unwrap();
+
+        assert_eq!(result.content.len(), 1);
+        assert_eq!(json_of(&result)[0]["title"], "Existing");
+    }
+
+    #[tokio::test]
+    async fn reset_requires_confirmation() {
+        let list = TodoList::new(Config::default());
+        create(&list, "A").await;
+        create(&list, "B").await;
+
+        let error = list
+            .reset(ResetRequest {
+                confirm: "yes".to_string(),
+                include_templates: None,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.data.unwrap()["expected_confirm"], RESET_CONFIRMATION);
+        assert_eq!(list.todos.lock().await.len(), 2);
+
+        let result = list
+            .reset(ResetRequest {
+                confirm: RESET_CONFIRMATION.to_string(),
+                include_templates: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(json_of(&result)["removed_todos"], 2);
+        assert!(list.todos.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn excess_tool_calls_queue_behind_the_limit() {
+        let list = TodoList::new(Config {
+            max_concurrent_calls: 2,
+            ..Config::default()
+        });
+
+        // Holding the store lock keeps every admitted call busy
+        let store = list.todos.lock().await;
+        let calls: Vec<_> = (0..5)
+            .map(|_| {
+                let list = list.clone();
+                tokio::spawn(async move { call(&list, "list_todos", json!({}), detached_context()).await })
+            })
+            .collect();
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(list.call_permits.available_permits(), 0);
+        assert!(calls.iter().all(|call| !call.is_finished()));
+
+        drop(store);
+        for call in calls {
+            assert!(call.await.unwrap().is_ok());
+        }
+        assert_eq!(list.call_permits.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn changed_since_flags_recent_updates_without_filtering() {
+        let list = TodoList::new(Config::default());
+        create(&list, "Old").await;
+        let fresh = create(&list, "Fresh").await;
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let threshold = Utc::now();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        list.update_todo(UpdateTodoRequest {
+            id: fresh,
+            title: Some("Fresh (edited)".to_string()),
+            description: None,
+            completed: None,
+        })
+        .await
+        .unwrap();
+
+        let result = list
+            .list_todos(ListTodosRequest {
+                changed_since: Some(threshold),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let items = json_of(&result);
+        assert_eq!(items.as_array().unwrap().len(), 2);
+        assert_eq!(items[0]["recently_changed"], false);
+        assert_eq!(items[1]["recently_changed"], true);
+    }
+
+    #[tokio::test]
+    async fn get_todo_returns_not_modified_marker_when_unchanged() {
+        let list = TodoList::new(Config::default());
+        let id = create(&list, "Poll me").await;
+        let updated_at = list.todos.lock().await[0].updated_at;
+
+        let result = list.get_todo(id.clone(), Some(updated_at), None).await.unwrap();
+        let marker = json_of(&result);
+        assert_eq!(marker["not_modified"], true);
+        assert!(marker.get("title").is_none());
+
+        let earlier = updated_at - chrono::Duration::seconds(1);
+        let result = list.get_todo(id, Some(earlier), None).await.unwrap();
+        let item = json_of(&result);
+        assert_eq!(item["title"], "Poll me");
+        assert!(item.get("not_modified").is_none());
+    }
+
+    /// Fresh path in the system temp directory
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("todo-test-{}-{}", Uuid::new_v4(), name))
+    }
+
+    /// ID, title and completion of every item, in list order
+    async fn snapshot(list: &TodoList) -> Vec<(String, String, bool)> {
+        list.todos
+            .lock()
+            .await
+            .iter()
+            .map(|t| (t.id.clone(), t.title.clone(), t.completed))
+            .collect()
+    }
+
+    /// Record of a successful call, as the call log would write it
+    fn record(tool: &str, arguments: Value) -> CallRecord {
+        CallRecord {
+            timestamp: Utc::now(),
+            tool: tool.to_string(),
+            arguments: arguments.as_object().cloned(),
+            caller: None,
+            status: CallStatus::Ok,
+            created_id: None,
+            redacted: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn replaying_call_log_rebuilds_the_store() {
+        let log_path = temp_path("calls.jsonl");
+        let recorded = TodoList::new(Config {
+            call_log_path: Some(log_path.clone()),
+            ..Config::default()
+        });
+        let create_a = call(&recorded, "create_todo", json!({"title": "A"}), detached_context()).await.unwrap();
+        let a = json_of(&create_a)["id"].as_str().unwrap().to_string();
+        let create_b = call(&recorded, "create_todo", json!({"title": "B"}), detached_context()).await.unwrap();
+        let b = json_of(&create_b)["id"].as_str().unwrap().to_string();
+        call(&recorded, "complete_todo", json!({"id": a}), detached_context()).await.unwrap();
+        call(&recorded, "update_todo", json!({"id": b, "title": "B (edited)"}), detached_context()).await.unwrap();
+        call(&recorded, "list_todos", json!({}), detached_context()).await.unwrap();
+        call(&recorded, "delete_todo", json!({"id": "missing"}), detached_context()).await.unwrap_err();
+        call(&recorded, "move_up", json!({"id": b}), detached_context()).await.unwrap();
+
+        let records = crate::call_log::read_records(&log_path).unwrap();
+        std::fs::remove_file(&log_path).unwrap();
+        assert_eq!(records.len(), 7);
+
+        let replayed = TodoList::new(Config::default());
+        let applied = replayed.replay(records).await.unwrap();
+
+        assert_eq!(applied, 5);
+        assert_eq!(snapshot(&replayed).await, snapshot(&recorded).await);
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_unknown_tools() {
+        let list = TodoList::new(Config::default());
+        let records = vec![
+            record("create_todo", json!({"title": "A"})),
+            record("archive_todo", json!({"id": "x"})),
+        ];
+
+        let error = list.replay(records).await.unwrap_err();
+
+        assert_eq!(error.data.unwrap()["tool"], "archive_todo");
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_redacted_arguments() {
+        let log_path = temp_path("redacted-calls.jsonl");
+        let recorded = TodoList::new(Config {
+            call_log_path: Some(log_path.clone()),
+            call_log_redact: vec!["description".to_string()],
+            ..Config::default()
+        });
+        call(&recorded, "create_todo", json!({"title": "[redacted]"}), detached_context()).await.unwrap();
+        call(&recorded, "create_todo", json!({"title": "Secret", "description": "pin 1234"}), detached_context()).await.unwrap();
+        let records = crate::call_log::read_records(&log_path).unwrap();
+        std::fs::remove_file(&log_path).unwrap();
+        assert_eq!(records[1].redacted, ["description"]);
+
+        let list = TodoList::new(Config::default());
+        let error = list.replay(records).await.unwrap_err();
+
+        assert!(error.message.contains("redacted"));
+        assert_eq!(error.data.unwrap()["record"], 2);
+        // A title that merely looks like the placeholder was replayed
+        assert_eq!(titles(&list).await, ["[redacted]"]);
+    }
+
+    #[tokio::test]
+    async fn get_todo_navigation_points_at_neighbours() {
+        let list = TodoList::new(Config::default());
+        let first = create(&list, "First").await;
+        let middle = create(&list, "Middle").await;
+        let last = create(&list, "Last").await;
+
+        let result = list.get_todo(middle, None, Some(true)).await.unwrap();
+        let item = json_of(&result);
+        assert_eq!(item["prev_id"], first.as_str());
+        assert_eq!(item["next_id"], last.as_str());
+
+        let result = list.get_todo(first, None, Some(true)).await.unwrap();
+        let item = json_of(&result);
+        assert!(item["prev_id"].is_null());
+        assert_eq!(json_of(&list.get_todo(last, None, None).await.unwrap()).get("next_id"), None);
+    }
+
+    /// Completed item whose completion happened at noon on `day`
+    fn completed_on(day: NaiveDate) -> TodoItem {
+        let mut item = TodoItem::new("Done".to_string(), None, None);
+        item.mark_completed(Utc::now());
+        item.completed_at = Some(day.and_hms_opt(12, 0, 0).unwrap().and_utc());
+        item
+    }
+
+    #[test]
+    fn completion_streak_counts_consecutive_days_and_resets_after_a_gap() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut todos = vec![completed_on(day(1))];
+        assert_eq!(completion_streak(&todos, day(1)), 1);
+
+        todos.push(completed_on(day(2)));
+        todos.push(completed_on(day(2)));
+        assert_eq!(completion_streak(&todos, day(2)), 2);
+
+        todos.push(completed_on(day(3)));
+        assert_eq!(completion_streak(&todos, day(3)), 3);
+        // Still alive the next day until it ends without a completion
+        assert_eq!(completion_streak(&todos, day(4)), 3);
+
+        // 4 March passed without a completion
+        assert_eq!(completion_streak(&todos, day(5)), 0);
+        todos.push(completed_on(day(5)));
+        assert_eq!(completion_streak(&todos, day(5)), 1);
+    }
+
+    #[tokio::test]
+    async fn undo_and_reopen_take_completions_out_of_the_streak() {
+        let list = TodoList::new(Config::default());
+        let streak = |list: &TodoList| {
+            let todos = list.todos.try_lock().unwrap();
+            completion_streak(&todos, Utc::now().date_naive())
+        };
+        let undone = create(&list, "Undone").await;
+        let reopened = create(&list, "Reopened").await;
+
+        let result = list.complete_todo(undone).await.unwrap();
+        assert_eq!(json_of(&result)["completion_streak"], 1);
+        list.undo().await.unwrap();
+        assert_eq!(streak(&list), 0);
+
+        list.complete_todo(reopened.clone()).await.unwrap();
+        let result = list.uncomplete_todo(reopened).await.unwrap();
+        assert_eq!(json_of(&result)["completion_streak"], 0);
+        let request = ListTodosRequest { include_stats: Some(true), ..Default::default() };
+        let stats = json_of(&list.list_todos(request).await.unwrap());
+        assert_eq!(stats["completion_streak"], 0);
+    }
+
+    #[tokio::test]
+    async fn validate_create_flags_empty_title_without_creating() {
+        let list = TodoList::new(Config::default());
+        let req = CreateTodoRequest { title: "  ".to_string(), description: None };
+
+        let report = json_of(&list.validate_create(req).await.unwrap());
+
+        assert_eq!(report["valid"], false);
+        assert_eq!(report["errors"].as_array().unwrap().len(), 1);
+        assert!(list.todos.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn title_length_cap_applies_only_when_configured() {
+        let long_title = || CreateTodoRequest { title: "x".repeat(300), description: None };
+
+        let uncapped = TodoList::new(Config::default());
+        let report = json_of(&uncapped.validate_create(long_title()).await.unwrap());
+        assert_eq!(report["valid"], true);
+
+        let capped = TodoList::new(Config { max_title_chars: Some(200), ..Config::default() });
+        let report = json_of(&capped.validate_create(long_title()).await.unwrap());
+        assert_eq!(report["valid"], false);
+        capped.create_todo(long_title()).await.unwrap_err();
+        assert!(capped.todos.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn complete_by_title_completes_a_unique_match() {
+        let list = TodoList::new(Config::default());
+        let id = create(&list, "Water plants").await;
+        create(&list, "Feed cat").await;
+
+        let result = list.complete_by_title("Water plants".to_string()).await.unwrap();
+
+        assert_eq!(json_of(&result)["id"], id.as_str());
+        let todos = list.todos.lock().await;
+        assert!(todos[0].completed);
+        assert!(!todos[1].completed);
+    }
+
+    #[tokio::test]
+    async fn complete_by_title_lists_candidates_when_ambiguous() {
+        let list = TodoList::new(Config::default());
+        let first = create(&list, "Call mum").await;
+        let second = create(&list, "Call mum").await;
+
+        let error = list.complete_by_title("Call mum".to_string()).await.unwrap_err();
+
+        assert_eq!(error.data.unwrap()["candidate_ids"], json!([first, second]));
+        assert!(list.todos.lock().await.iter().all(|t| !t.completed));
+    }
+
+    #[tokio::test]
+    async fn paused_items_move_from_list_todos_to_list_paused() {
+        let list = TodoList::new(Config::default());
+        let paused = create(&list, "On hold").await;
+        create(&list, "Active").await;
+
+        list.pause_todo(paused.clone()).await.unwrap();
+
+        let pending = json_of(&list.list_todos(ListTodosRequest::default()).await.unwrap());
+        assert_eq!(pending.as_array().unwrap().len(), 1);
+        assert_eq!(pending[0]["title"], "Active");
+        let on_hold = json_of(&list.list_paused().await.unwrap());
+        assert_eq!(on_hold.as_array().unwrap().len(), 1);
+        assert_eq!(on_hold[0]["id"], paused.as_str());
+
+        list.resume_todo(paused).await.unwrap();
+        let pending = json_of(&list.list_todos(ListTodosRequest::default()).await.unwrap());
+        assert_eq!(pending.as_array().unwrap().len(), 2);
+        assert!(json_of(&list.list_paused().await.unwrap()).as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn instructions_describe_every_registered_tool() {
+        let list = TodoList::new(Config::default());
+        let instructions = list.get_info().instructions.unwrap();
+
+        for tool in TodoList::tool_box().list() {
+            let line = format!("- {}: {}", tool.name, tool_summary(&tool.description));
+            assert!(instructions.contains(&line), "missing {}", line);
+        }
+        assert!(instructions.contains("- create_todo: Create a new todo item"));
+        assert!(instructions.contains("- pause_todo: Put a todo item on hold"));
+        assert!(instructions.contains("list_tool_schemas"));
+    }
+
+    #[tokio::test]
+    async fn work_sessions_accumulate_across_start_stop_cycles() {
+        let list = TodoList::new(Config::default());
+        let id = create(&list, "Refactor parser").await;
+
+        list.stop_todo(id.clone()).await.unwrap_err();
+        list.start_todo(id.clone()).await.unwrap();
+        list.start_todo(id.clone()).await.unwrap_err();
+        backdate_session(&list, &id, 10).await;
+        list.stop_todo(id.clone()).await.unwrap();
+
+        list.start_todo(id.clone()).await.unwrap();
+        backdate_session(&list, &id, 20).await;
+        let result = list.stop_todo(id.clone()).await.unwrap();
+
+        let item = json_of(&result);
+        assert_eq!(item["actual_minutes"], 30);
+        assert_eq!(item["work_sessions"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn completing_through_update_todo_stops_the_session() {
+        let list = TodoList::new(Config::default());
+        let id = create(&list, "Review PR").await;
+        list.start_todo(id.clone()).await.unwrap();
+        backdate_session(&list, &id, 15).await;
+
+        let req = UpdateTodoRequest { id: id.clone(), title: None, description: None, completed: Some(true) };
+        list.update_todo(req).await.unwrap();
+
+        let todos = list.todos.lock().await;
+        assert_eq!(todos[0].actual_minutes, Some(15));
+        assert!(todos[0].work_sessions[0].ended_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn export_html_renders_each_item_and_escapes_titles() {
+        let list = TodoList::new(Config::default());
+        let done = create(&list, "Ship release").await;
+        create(&list, "<script>alert(1)</script>").await;
+        list.complete_todo(done.clone()).await.unwrap();
+
+        let result = list.export_html().await.unwrap();
+        let html = text(&result);
+
+        assert_eq!(html.matches("<li ").count(), 2);
+        assert!(html.contains(&format!("<li id=\"todo-{}\" class=\"completed\">", done)));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[tokio::test]
+    async fn deduplicating_create_returns_the_existing_item() {
+        let list = TodoList::new(Config { deduplicate_on_create: true, ..Config::default() });
+        let original = create(&list, "Buy milk").await;
+
+        let result = list
+            .create_todo(CreateTodoRequest { title: "  buy   MILK ".to_string(), description: None })
+            .await
+            .unwrap();
+
+        let item = json_of(&result);
+        assert_eq!(item["id"], original.as_str());
+        assert_eq!(item["deduplicated"], true);
+        assert_eq!(list.todos.lock().await.len(), 1);
+
+        let plain = TodoList::new(Config::default());
+        assert_ne!(create(&plain, "Buy milk").await, create(&plain, "Buy milk").await);
+    }
+
+    #[tokio::test]
+    async fn bulk_update_clears_only_listed_fields() {
+        let list = TodoList::new(Config::default());
+        let mut ids = Vec::new();
+        for title in ["A", "B", "C"] {
+            let result = list
+                .create_todo(CreateTodoRequest { title: title.to_string(), description: Some("notes".to_string()) })
+                .await
+                .unwrap();
+            ids.push(json_of(&result)["id"].as_str().unwrap().to_string());
+        }
+
+        let req = BulkUpdateRequest {
+            ids: ids[..2].to_vec(),
+            fields: vec!["description".to_string()],
+            values: json!({"description": null}).as_object().cloned().unwrap(),
+        };
+        let result = list.bulk_update(req).await.unwrap();
+        assert_eq!(json_of(&result)["updated"], 2);
+
+        let todos = list.todos.lock().await;
+        assert_eq!(todos[0].description, None);
+        assert_eq!(todos[1].description, None);
+        assert_eq!(todos[2].description.as_deref(), Some("notes"));
+        assert_eq!(todos[0].title, "A");
+        assert!(!todos[0].completed);
+    }
+
+    #[tokio::test]
+    async fn bulk_update_completion_ends_sessions_and_reports_streak() {
+        let list = TodoList::new(Config::default());
+        let id = create(&list, "Tidy desk").await;
+        list.start_todo(id.clone()).await.unwrap();
+        backdate_session(&list, &id, 5).await;
+
+        let req = BulkUpdateRequest {
+            ids: vec![id],
+            fields: vec!["completed".to_string()],
+            values: json!({"completed": true}).as_object().cloned().unwrap(),
+        };
+        let result = json_of(&list.bulk_update(req).await.unwrap());
+
+        assert_eq!(result["completed"], 1);
+        assert_eq!(result["completion_streak"], 1);
+        assert_eq!(result["items"][0]["actual_minutes"], 5);
+    }
+
+    #[tokio::test]
+    async fn list_todos_serves_repeated_filters_from_cache_until_a_mutation() {
+        let list = TodoList::new(Config::default());
+        create(&list, "Cached").await;
+        let filtered = || ListTodosRequest { include_stats: Some(true), limit: Some(10), ..Default::default() };
+
+        let first = text(&list.list_todos(filtered()).await.unwrap()).to_string();
+        // Bypasses invalidation, so only a cache hit still shows the old title
+        list.todos.lock().await[0].title = "Changed behind the cache".to_string();
+        let second = text(&list.list_todos(filtered()).await.unwrap()).to_string();
+        assert_eq!(first, second);
+        assert_eq!(list.list_cache.lock().await.len(), 1);
+
+        let unfiltered = json_of(&list.list_todos(ListTodosRequest::default()).await.unwrap());
+        assert_eq!(unfiltered[0]["title"], "Changed behind the cache");
+        assert_eq!(list.list_cache.lock().await.len(), 2);
+
+        create(&list, "Invalidates").await;
+        assert!(list.list_cache.lock().await.is_empty());
+        let third = json_of(&list.list_todos(filtered()).await.unwrap());
+        assert_eq!(third["items"].as_array().unwrap().len(), 2);
+    }
+
+    /// Request context of a client that initialized with the given name
+    fn client_context(name: &str) -> RequestContext<RoleServer> {
+        let mut info = ClientInfo::default();
+        info.client_info.name = name.to_string();
+        let (peer, _outbound) = Peer::new(Arc::new(AtomicU32RequestIdProvider::default()), info);
+        RequestContext {
+            ct: Default::default(),
+            id: RequestId::Number(0),
+            peer,
+        }
+    }
+
+    #[tokio::test]
+    async fn client_identity_is_recorded_on_create_and_complete() {
+        let list = TodoList::new(Config::default());
+        let created = call(&list, "create_todo", json!({"title": "Shared"}), client_context("agent-a")).await.unwrap();
+        let id = json_of(&created)["id"].as_str().unwrap().to_string();
+        assert_eq!(json_of(&created)["created_by"], "agent-a");
+
+        let completed = call(&list, "complete_todo", json!({"id": id}), client_context("agent-b")).await.unwrap();
+        assert_eq!(json_of(&completed)["completed_by"], "agent-b");
+        assert_eq!(json_of(&completed)["created_by"], "agent-a");
+
+        let anonymous = call(&list, "create_todo", json!({"title": "Anonymous"}), client_context(" ")).await.unwrap();
+        let id = json_of(&anonymous)["id"].as_str().unwrap().to_string();
+        assert!(json_of(&anonymous)["created_by"].is_null());
+        let completed = call(&list, "complete_todo", json!({"id": id}), client_context("")).await.unwrap();
+        assert!(json_of(&completed)["completed_by"].is_null());
+    }
+
+    #[tokio::test]
+    async fn list_by_author_returns_only_that_clients_items() {
+        let list = TodoList::new(Config::default());
+        for (title, client) in [("A1", "agent-a"), ("B1", "agent-b"), ("A2", "agent-a")] {
+            call(&list, "create_todo", json!({"title": title}), client_context(client)).await.unwrap();
+        }
+        // Editing an item makes the editor one of its authors too
+        let b1 = list.todos.lock().await[1].id.clone();
+        call(&list, "update_todo", json!({"id": b1, "title": "B1 (edited)"}), client_context("agent-c")).await.unwrap();
+
+        let titles_by = |items: Value| -> Vec<String> {
+            items.as_array().unwrap().iter().map(|t| t["title"].as_str().unwrap().to_string()).collect()
+        };
+        let by_a = json_of(&list.list_by_author("agent-a".to_string()).await.unwrap());
+        assert_eq!(titles_by(by_a), ["A1", "A2"]);
+        let by_b = json_of(&list.list_by_author("agent-b".to_string()).await.unwrap());
+        assert_eq!(titles_by(by_b), ["B1 (edited)"]);
+        let by_c = json_of(&list.list_by_author("agent-c".to_string()).await.unwrap());
+        assert_eq!(titles_by(by_c), ["B1 (edited)"]);
+    }
+
+    #[tokio::test]
+    async fn uncompleting_within_grace_period_cancels_the_webhook() {
+        let (listener, url) = webhook_listener().await;
+        let list = TodoList::new(Config {
+            webhook_url: Some(url),
+            completion_grace_period: std::time::Duration::from_millis(200),
+            ..Config::default()
+        });
+        let mistake = create(&list, "Completed by mistake").await;
+        let kept = create(&list, "Really done").await;
+
+        list.complete_todo(mistake.clone()).await.unwrap();
+        let result = list.uncomplete_todo(mistake).await.unwrap();
+        let item = json_of(&result);
+        assert_eq!(item["within_grace_period"], true);
+        assert_eq!(item["completion_streak"], 0);
+        list.complete_todo(kept.clone()).await.unwrap();
+
+        // Only the completion that survived its grace period is sent
+        let body = tokio::time::timeout(std::time::Duration::from_secs(5), receive_webhook(&listener))
+            .await
+            .expect("webhook POST");
+        assert_eq!(body["id"], kept);
+        let late = tokio::time::timeout(std::time::Duration::from_millis(500), listener.accept()).await;
+        assert!(late.is_err(), "webhook fired for an uncompleted item");
+    }
+
+    #[tokio::test]
+    async fn export_to_file_writes_every_item_inside_the_export_dir() {
+        let dir = temp_path("export");
+        std::fs::create_dir_all(dir.join("backups")).unwrap();
+        let list = TodoList::new(Config { export_dir: Some(dir.clone()), ..Config::default() });
+        create(&list, "First").await;
+        create(&list, "Second").await;
+
+        let result = json_of(&list.export_to_file("backups/todos.json".to_string()).await.unwrap());
+        assert_eq!(result["count"], 2);
+        let written: Vec<TodoItem> = serde_json::from_slice(&std::fs::read(dir.join("backups/todos.json")).unwrap()).unwrap();
+        let written: Vec<_> = written.into_iter().map(|t| t.title).collect();
+        assert_eq!(written, titles(&list).await);
+
+        list.export_to_file("../escape.json".to_string()).await.unwrap_err();
+        list.export_to_file("/tmp/escape.json".to_string()).await.unwrap_err();
+        #[cfg(unix)]
+        {
+            let outside = temp_path("outside.json");
+            std::os::unix::fs::symlink(&outside, dir.join("link.json")).unwrap();
+            list.export_to_file("link.json".to_string()).await.unwrap_err();
+            assert!(!outside.exists());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn export_to_file_is_disabled_without_an_export_dir() {
+        let list = TodoList::new(Config::default());
+        list.export_to_file("todos.json".to_string()).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn configured_default_limit_truncates_list_todos() {
+        let list = TodoList::new(Config { list_default_limit: Some(50), ..Config::default() });
+        for n in 0..100 {
+            create(&list, &format!("Item {}", n)).await;
+        }
+
+        let page = json_of(&list.list_todos(ListTodosRequest::default()).await.unwrap());
+        assert_eq!(page["items"].as_array().unwrap().len(), 50);
+        assert_eq!(page["truncated"], true);
+        assert_eq!(page["total"], 100);
+
+        let everything = json_of(&list.list_todos(ListTodosRequest { all: Some(true), ..Default::default() }).await.unwrap());
+        assert_eq!(everything.as_array().unwrap().len(), 100);
+        let larger = json_of(&list.list_todos(ListTodosRequest { limit: Some(200), ..Default::default() }).await.unwrap());
+        assert_eq!(larger["items"].as_array().unwrap().len(), 100);
+        assert_eq!(larger["truncated"], false);
+    }
+
+    #[tokio::test]
+    async fn list_todos_is_unbounded_by_default() {
+        let list = TodoList::new(Config::default());
+        for n in 0..60 {
+            create(&list, &format!("Item {}", n)).await;
+        }
+
+        let items = json_of(&list.list_todos(ListTodosRequest::default()).await.unwrap());
+        assert_eq!(items.as_array().unwrap().len(), 60);
+    }
+
+    #[tokio::test]
+    async fn seeded_sample_is_reproducible_and_sized() {
+        let list = TodoList::new(Config::default());
+        for n in 0..20 {
+            create(&list, &format!("Item {}", n)).await;
+        }
+        let sample = |seed| ListTodosRequest { sample: Some(5), seed: Some(seed), ..Default::default() };
+
+        let first = json_of(&list.list_todos(sample(7)).await.unwrap());
+        list.invalidate_list_cache().await;
+        let again = json_of(&list.list_todos(sample(7)).await.unwrap());
+
+        assert_eq!(first.as_array().unwrap().len(), 5);
+        assert_eq!(first, again);
+        let oversized = ListTodosRequest { sample: Some(50), seed: Some(7), ..Default::default() };
+        assert_eq!(json_of(&list.list_todos(oversized).await.unwrap()).as_array().unwrap().len(), 20);
+    }
+
+    #[tokio::test]
+    async fn sample_cannot_be_combined_with_limit_or_all() {
+        let list = TodoList::new(Config::default());
+        create(&list, "Only").await;
+
+        let with_limit = ListTodosRequest { sample: Some(1), limit: Some(1), ..Default::default() };
+        list.list_todos(with_limit).await.unwrap_err();
+        let with_all = ListTodosRequest { sample: Some(1), all: Some(true), ..Default::default() };
+        list.list_todos(with_all).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn rating_is_stored_and_averaged() {
+        let list = TodoList::new(Config::default());
+        let rated = create(&list, "Rated").await;
+        let unrated = create(&list, "Unrated").await;
+        let out_of_range = create(&list, "Out of range").await;
+
+        let req = CompleteWithFeedbackRequest { id: rated, rating: Some(4), feedback: Some("Went fine".to_string()) };
+        let item = json_of(&list.complete_with_feedback(req).await.unwrap());
+        assert_eq!(item["rating"], 4);
+        assert_eq!(item["feedback"], "Went fine");
+        list.complete_todo(unrated).await.unwrap();
+
+        let req = CompleteWithFeedbackRequest { id: out_of_range, rating: Some(6), feedback: None };
+        list.complete_with_feedback(req).await.unwrap_err();
+        assert!(!list.todos.lock().await[2].completed);
+
+        let stats = json_of(&list.average_rating().await.unwrap());
+        assert_eq!(stats["average_rating"], 4.0);
+        assert_eq!(stats["rated"], 1);
+    }
+
+    #[tokio::test]
+    async fn list_new_since_last_view_returns_changes_after_the_clients_view() {
+        let list = TodoList::new(Config::default());
+        let first = create(&list, "First").await;
+        let second = create(&list, "Second").await;
+
+        call(&list, "list_todos", json!({}), client_context("viewer")).await.unwrap();
+        call(&list, "update_todo", json!({"id": second, "title": "Second (edited)"}), client_context("editor")).await.unwrap();
+
+        let new = json_of(&call(&list, "list_new_since_last_view", json!({}), client_context("viewer")).await.unwrap());
+        let items = new["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], second.as_str());
+        // A client that never looked sees everything
+        let new = json_of(&call(&list, "list_new_since_last_view", json!({}), client_context("newcomer")).await.unwrap());
+        assert_eq!(new["items"].as_array().unwrap().len(), 2);
+
+        // A truncated view doesn't count as having seen the list
+        call(&list, "update_todo", json!({"id": first, "title": "First (edited)"}), client_context("editor")).await.unwrap();
+        call(&list, "list_todos", json!({"limit": 1}), client_context("viewer")).await.unwrap();
+        let new = json_of(&call(&list, "list_new_since_last_view", json!({}), client_context("viewer")).await.unwrap());
+        let items = new["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], first.as_str());
+    }
+
+    #[tokio::test]
+    async fn delete_todo_returns_the_deleted_item() {
+        let list = TodoList::new(Config::default());
+        let result = list
+            .create_todo(CreateTodoRequest { title: "Obsolete".to_string(), description: Some("No longer needed".to_string()) })
+            .await
+            .unwrap();
+        let created = json_of(&result);
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let result = list.delete_todo(id.clone()).await.unwrap();
+
+        assert_eq!(result.content.len(), 1);
+        let response = json_of(&result);
+        assert!(response["message"].as_str().unwrap().contains(&id));
+        assert_eq!(response["deleted"], created);
+        assert_eq!(response["deleted"]["title"], "Obsolete");
+        assert_eq!(response["deleted"]["description"], "No longer needed");
+        assert!(list.todos.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn replayed_completions_do_not_fire_the_webhook() {
+        let (listener, url) = webhook_listener().await;
+        let list = TodoList::new(Config {
+            webhook_url: Some(url),
+            completion_grace_period: std::time::Duration::ZERO,
+            ..Config::default()
+        });
+        let mut created = record("create_todo", json!({"title": "Done last week"}));
+        created.created_id = Some("recorded-id".to_string());
+        let records = vec![created, record("complete_todo", json!({"id": "recorded-id"}))];
+
+        assert_eq!(list.replay(records).await.unwrap(), 2);
+
+        assert!(list.todos.lock().await[0].completed);
+        let posted = tokio::time::timeout(std::time::Duration::from_millis(500), listener.accept()).await;
+        assert!(posted.is_err(), "webhook fired for a replayed completion");
+    }
+
+    #[tokio::test]
+    async fn list_total_means_the_same_with_and_without_stats() {
+        let list = TodoList::new(Config::default());
+        let paused = create(&list, "Paused").await;
+        create(&list, "First").await;
+        create(&list, "Second").await;
+        list.pause_todo(paused).await.unwrap();
+
+        let page = json_of(&list.list_todos(ListTodosRequest { limit: Some(1), ..Default::default() }).await.unwrap());
+        let with_stats = ListTodosRequest { limit: Some(1), include_stats: Some(true), ..Default::default() };
+        let stats = json_of(&list.list_todos(with_stats).await.unwrap());
+
+        assert_eq!(page["total"], 2);
+        assert_eq!(stats["total"], page["total"]);
+        assert_eq!(stats["truncated"], page["truncated"]);
+        assert_eq!(stats["store_total"], 3);
+        assert_eq!(stats["paused"], 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_webhook_delivery_is_not_retried() {
+        let (listener, url) = webhook_listener().await;
+        let list = TodoList::new(Config {
+            webhook_url: Some(url),
+            completion_grace_period: std::time::Duration::ZERO,
+            ..Config::default()
+        });
+        let id = create(&list, "Rejected").await;
+
+        list.complete_todo(id.clone()).await.unwrap();
+
+        let body = tokio::time::timeout(std::time::Duration::from_secs(5), answer_webhook(&listener, "400 Bad Request"))
+            .await
+            .expect("webhook POST");
+        assert_eq!(body["id"], id);
+        // The first retry would arrive after 500ms
+        let retry = tokio::time::timeout(std::time::Duration::from_millis(1500), listener.accept()).await;
+        assert!(retry.is_err(), "webhook retried after a 4xx response");
+    }
+
+    #[tokio::test]
+    async fn failed_webhook_delivery_is_retried() {
+        let (listener, url) = webhook_listener().await;
+        let list = TodoList::new(Config {
+            webhook_url: Some(url),
+            completion_grace_period: std::time::Duration::ZERO,
+            ..Config::default()
+        });
+        let id = create(&list, "Retried").await;
+
+        list.complete_todo(id.clone()).await.unwrap();
+
+        let timeout = std::time::Duration::from_secs(5);
+        tokio::time::timeout(timeout, answer_webhook(&listener, "503 Service Unavailable")).await.expect("first POST");
+        let body = tokio::time::timeout(timeout, receive_webhook(&listener)).await.expect("retried POST");
+        assert_eq!(body["id"], id);
+    }
+
+    #[tokio::test]
+    async fn get_todo_navigation_skips_paused_items() {
+        let list = TodoList::new(Config::default());
+        let first = create(&list, "First").await;
+        let paused = create(&list, "Paused").await;
+        let last = create(&list, "Last").await;
+        list.pause_todo(paused.clone()).await.unwrap();
+
+        let item = json_of(&list.get_todo(first.clone(), None, Some(true)).await.unwrap());
+        assert_eq!(item["next_id"], last.as_str());
+        let item = json_of(&list.get_todo(last.clone(), None, Some(true)).await.unwrap());
+        assert_eq!(item["prev_id"], first.as_str());
+
+        // A paused item still knows where it sits among the visible ones
+        let item = json_of(&list.get_todo(paused, None, Some(true)).await.unwrap());
+        assert_eq!(item["prev_id"], first.as_str());
+        assert_eq!(item["next_id"], last.as_str());
+    }
+}
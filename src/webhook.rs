@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::todo::TodoItem;
+
+/// Number of delivery attempts before a notification is dropped
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled for each further attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest a single delivery attempt may take, including the response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outbound webhook notified when todo items are completed
+#[derive(Clone)]
+pub struct Webhook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl Webhook {
+    pub fn new(url: String) -> Self {
+        Self {
+            // Same failure mode as `Client::new`, which panics if TLS can't initialize
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("Failed to build webhook HTTP client"),
+            url,
+        }
+    }
+
+    /// POST the completed item to the webhook in the background
+    ///
+    /// Delivery is retried with exponential backoff unless the receiver
+    /// rejects it with a 4xx; failures are logged and never reported back to
+    /// the tool caller.
+    pub fn notify_completed(&self, item: &TodoItem) {
+        let body = match serde_json::to_value(item) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("Failed to serialize todo item {} for webhook: {}", item.id, e);
+                return;
+            },
+        };
+
+        let webhook = self.clone();
+        tokio::spawn(async move { webhook.deliver(body).await });
+    }
+
+    async fn deliver(&self, body: Value) {
+        let mut delay = RETRY_BASE_DELAY;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let result = self
+                .client
+                .post(&self.url)
+                .json(&body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match result {
+                Ok(_) => return,
+                // The receiver rejected the request itself; sending it again won't help
+                Err(e) if e.status().is_some_and(|status| status.is_client_error()) => {
+                    tracing::error!("Webhook delivery to {} was rejected, not retrying: {}", self.url, e);
+                    return;
+                },
+                Err(e) => tracing::warn!(
+                    "Webhook delivery to {} failed (attempt {}/{}): {}",
+                    self.url,
+                    attempt,
+                    MAX_ATTEMPTS,
+                    e,
+                ),
+            }
+
+            if attempt < MAX_ATTEMPTS {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
+        tracing::error!("Giving up on webhook delivery to {} after {} attempts", self.url, MAX_ATTEMPTS);
+    }
+}